@@ -6,7 +6,22 @@ pub struct GameConfig {
     pub play_sfx: bool,
     pub sfx_volume: f32,
     pub play_bgm: bool,
-    pub bgm_volume: f32
+    pub bgm_volume: f32,
+    pub hunger: HungerConfig,
+}
+
+/// Balance knobs for the food clock. Satiation counts down by `per_turn` from `max`, and the thresholds
+/// mark where the player turns hungry, weak and then fainting.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HungerConfig {
+    pub max: i32,
+    pub per_turn: i32,
+    pub hungry_below: i32,
+    pub weak_below: i32,
+    pub fainting_below: i32,
+    pub faint_chance: i32,
+    pub starve_damage: i32,
+    pub ration_nutrition: i32,
 }
 
 pub fn load_configs() -> GameConfig {
@@ -16,6 +31,16 @@ pub fn load_configs() -> GameConfig {
         play_sfx: config.getbool("audio", "play_sfx").unwrap().unwrap_or(true),
         sfx_volume: config.getfloat("audio", "sfx_volume").unwrap().unwrap_or(0.0) as f32,
         play_bgm: config.getbool("audio", "play_bgm").unwrap().unwrap_or(true),
-        bgm_volume: config.getfloat("audio", "bgm_volume").unwrap().unwrap_or(0.0) as f32
+        bgm_volume: config.getfloat("audio", "bgm_volume").unwrap().unwrap_or(0.0) as f32,
+        hunger: HungerConfig {
+            max: config.getint("hunger", "max").unwrap().unwrap_or(2000) as i32,
+            per_turn: config.getint("hunger", "per_turn").unwrap().unwrap_or(1) as i32,
+            hungry_below: config.getint("hunger", "hungry_below").unwrap().unwrap_or(500) as i32,
+            weak_below: config.getint("hunger", "weak_below").unwrap().unwrap_or(200) as i32,
+            fainting_below: config.getint("hunger", "fainting_below").unwrap().unwrap_or(60) as i32,
+            faint_chance: config.getint("hunger", "faint_chance").unwrap().unwrap_or(10) as i32,
+            starve_damage: config.getint("hunger", "starve_damage").unwrap().unwrap_or(1) as i32,
+            ration_nutrition: config.getint("hunger", "ration_nutrition").unwrap().unwrap_or(900) as i32,
+        }
     }
 }
\ No newline at end of file
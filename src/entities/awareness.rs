@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use tcod::Color;
+use tcod::colors::{LIGHT_BLUE, LIGHT_GREY, ORANGE, RED, YELLOW};
+
+use crate::entities::faction::Faction;
+
+pub const DEFAULT_SIGHT_RADIUS: i32 = 8;
+// number of turns a monster keeps looking for its target after losing sight of it
+pub const SEARCH_TURNS: i32 = 8;
+// a sleeping monster wakes up when the player comes this close, seen or not. Every point of stealth shrinks it by a tile
+pub const WAKE_RADIUS: i32 = 4;
+// how loud a noise has to be when it reaches a sleeping monster to wake it up
+pub const WAKE_VOLUME: i32 = 3;
+
+/// What a monster is currently up to. The monster's ai decides how it fights, but only once it's hunting.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AiState {
+    Asleep,
+    Wandering,
+    Hunting,
+    Searching,
+    Fleeing,
+}
+
+impl AiState {
+    // marker shown above the monster by the ai debug overlay
+    pub fn symbol(self) -> char {
+        use AiState::*;
+        match self {
+            Asleep => 'z',
+            Wandering => '~',
+            Hunting => '!',
+            Searching => '?',
+            Fleeing => '<',
+        }
+    }
+
+    pub fn color(self) -> Color {
+        use AiState::*;
+        match self {
+            Asleep => LIGHT_BLUE,
+            Wandering => LIGHT_GREY,
+            Hunting => RED,
+            Searching => YELLOW,
+            Fleeing => ORANGE,
+        }
+    }
+}
+
+impl std::fmt::Display for AiState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use AiState::*;
+        match *self {
+            Asleep => write!(f, "asleep"),
+            Wandering => write!(f, "wandering"),
+            Hunting => write!(f, "hunting"),
+            Searching => write!(f, "searching"),
+            Fleeing => write!(f, "fleeing"),
+        }
+    }
+}
+
+/// What a monster knows about its surroundings: how far it can see, where it last saw whatever it's after,
+/// what it's doing about it, and which faction, if any, has provoked it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Awareness {
+    pub sight_radius: i32,
+    pub last_known_target_pos: Option<(i32, i32)>,
+    pub search_turns: i32,
+    pub state: AiState,
+    pub wander_target: Option<(i32, i32)>,
+    pub provoked_by: Option<Faction>,
+}
+
+impl Awareness {
+    pub fn new(sight_radius: i32) -> Self {
+        Awareness {
+            sight_radius,
+            last_known_target_pos: None,
+            search_turns: 0,
+            state: AiState::Wandering,
+            wander_target: None,
+            provoked_by: None,
+        }
+    }
+
+    pub fn spot_target(&mut self, pos: (i32, i32)) {
+        self.last_known_target_pos = Some(pos);
+        self.search_turns = SEARCH_TURNS;
+    }
+
+    pub fn forget_target(&mut self) {
+        self.last_known_target_pos = None;
+        self.search_turns = 0;
+    }
+
+    /// Reacts to a noise from the given position: a loud enough one wakes a sleeping monster, and anything
+    /// awake that isn't already busy with a target goes to investigate.
+    pub fn hear_noise(&mut self, origin: (i32, i32), volume: i32) {
+        if self.state == AiState::Asleep && volume < WAKE_VOLUME {
+            return;
+        }
+        self.alert(origin);
+    }
+
+    /// Sends the monster to look around the given position, unless it's already busy with a target.
+    pub fn alert(&mut self, pos: (i32, i32)) {
+        if self.state != AiState::Hunting && self.state != AiState::Fleeing {
+            self.spot_target(pos);
+            self.state = AiState::Searching;
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use tcod::Color;
+use tcod::colors::{DARK_CRIMSON, DARK_GREEN};
+
+use crate::entities::combat::DamageRange;
+use crate::entities::damage::{DamageType, Resistances};
+use crate::entities::faction::Faction;
+use crate::entities::fighter::Fighter;
+use crate::entities::monster::Monster;
+use crate::items::item::Item;
+use crate::util::death_callback::DeathCallback;
+
+/// The bosses waiting at the bottom of a boss level. Each one is keyed to a template describing how its fight plays out.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Boss {
+    BoneLord,
+    OrcWarlord,
+}
+
+impl Boss {
+    pub fn template(self) -> &'static BossTemplate {
+        match self {
+            Boss::BoneLord => &BONE_LORD,
+            Boss::OrcWarlord => &ORC_WARLORD,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BossAbility {
+    /// Marks the ground around the boss, then brings it down on anyone who didn't get clear.
+    Slam { radius: i32, damage: i32 },
+    /// Calls in `count` more minions, as long as fewer than `max_minions` are still standing.
+    Summon { monster: Monster, count: i32, max_minions: i32 },
+    /// Sets tiles around the target smouldering, and a couple of turns later they burst into flame.
+    Eruption { tiles: i32, damage: i32 },
+}
+
+impl std::fmt::Display for BossAbility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BossAbility::Slam { .. } => "slam",
+            BossAbility::Summon { .. } => "summon",
+            BossAbility::Eruption { .. } => "eruption",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhaseAbility {
+    pub ability: BossAbility,
+    pub cooldown: i32,
+}
+
+/// One stage of a boss fight. The boss moves on to a phase once its hp drops to `hp_percent` or lower,
+/// and announces it, unless it's the phase the fight starts in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BossPhase {
+    pub hp_percent: i32,
+    pub announcement: Option<&'static str>,
+    pub abilities: &'static [PhaseAbility],
+}
+
+/// What a boss looks like, how it fights, phase by phase, and what it's guaranteed to leave behind.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BossTemplate {
+    pub name: &'static str,
+    pub char: char,
+    pub color: Color,
+    pub fighter: Fighter,
+    pub faction: Faction,
+    pub phases: &'static [BossPhase],
+    pub loot: &'static [Item],
+}
+
+impl BossTemplate {
+    // the deepest phase the boss has reached at the given hp; phases are listed from the start of the fight
+    pub fn phase_at(&self, hp_percent: i32) -> usize {
+        self.phases.iter().rposition(|phase| hp_percent <= phase.hp_percent).unwrap_or(0)
+    }
+}
+
+pub const BONE_LORD: BossTemplate = BossTemplate {
+    name: "Bone Lord",
+    char: 'B',
+    color: DARK_CRIMSON,
+    fighter: Fighter {
+        base_max_hp: 60,
+        hp: 60,
+        base_defense: 2,
+        base_power: 5,
+        accuracy: 10,
+        evasion: 5,
+        stealth: 0,
+        damage: DamageRange { min: 1, max: 4 },
+        xp: 0,
+        xp_reward: 1000,
+        damage_type: DamageType::Necrotic,
+        resistances: Resistances { physical: 0, fire: 0, lightning: 0, cold: 50, necrotic: 100 },
+        on_death: DeathCallback::Boss,
+    },
+    faction: Faction::Undead,
+    phases: &[
+        BossPhase {
+            hp_percent: 100,
+            announcement: None,
+            abilities: &[
+                PhaseAbility { ability: BossAbility::Slam { radius: 1, damage: 8 }, cooldown: 4 },
+            ],
+        },
+        BossPhase {
+            hp_percent: 60,
+            announcement: Some("The Bone Lord howls, and the dead claw their way up to answer!"),
+            abilities: &[
+                PhaseAbility { ability: BossAbility::Summon { monster: Monster::Skeleton, count: 2, max_minions: 4 }, cooldown: 10 },
+                PhaseAbility { ability: BossAbility::Slam { radius: 1, damage: 8 }, cooldown: 4 },
+            ],
+        },
+        BossPhase {
+            hp_percent: 25,
+            announcement: Some("Grave-fire kindles in the Bone Lord's eyes, and the crypt floor starts to smoulder!"),
+            abilities: &[
+                PhaseAbility { ability: BossAbility::Eruption { tiles: 5, damage: 5 }, cooldown: 5 },
+                PhaseAbility { ability: BossAbility::Slam { radius: 1, damage: 10 }, cooldown: 3 },
+                PhaseAbility { ability: BossAbility::Summon { monster: Monster::Skeleton, count: 1, max_minions: 4 }, cooldown: 12 },
+            ],
+        },
+    ],
+    loot: &[
+        Item::Artifact { name: String::new(), value: 0 },
+        Item::RaiseSkeleton,
+    ],
+};
+
+pub const ORC_WARLORD: BossTemplate = BossTemplate {
+    name: "Orc Warlord",
+    char: 'W',
+    color: DARK_GREEN,
+    fighter: Fighter {
+        base_max_hp: 100,
+        hp: 100,
+        base_defense: 4,
+        base_power: 8,
+        accuracy: 15,
+        evasion: 5,
+        stealth: 0,
+        damage: DamageRange { min: 2, max: 5 },
+        xp: 0,
+        xp_reward: 2000,
+        damage_type: DamageType::Physical,
+        resistances: Resistances { physical: 10, fire: 0, lightning: 0, cold: 0, necrotic: 0 },
+        on_death: DeathCallback::Boss,
+    },
+    faction: Faction::Greenskins,
+    phases: &[
+        BossPhase {
+            hp_percent: 100,
+            announcement: None,
+            abilities: &[
+                PhaseAbility { ability: BossAbility::Slam { radius: 1, damage: 10 }, cooldown: 5 },
+                PhaseAbility { ability: BossAbility::Summon { monster: Monster::Orc, count: 2, max_minions: 4 }, cooldown: 12 },
+            ],
+        },
+        BossPhase {
+            hp_percent: 50,
+            announcement: Some("The Orc Warlord bellows a war cry, and drums answer from the dark!"),
+            abilities: &[
+                PhaseAbility { ability: BossAbility::Summon { monster: Monster::Orc, count: 3, max_minions: 6 }, cooldown: 8 },
+                PhaseAbility { ability: BossAbility::Slam { radius: 2, damage: 10 }, cooldown: 5 },
+            ],
+        },
+        BossPhase {
+            hp_percent: 20,
+            announcement: Some("Cornered, the Orc Warlord starts hurling pots of burning pitch!"),
+            abilities: &[
+                PhaseAbility { ability: BossAbility::Eruption { tiles: 6, damage: 6 }, cooldown: 4 },
+                PhaseAbility { ability: BossAbility::Slam { radius: 2, damage: 12 }, cooldown: 4 },
+            ],
+        },
+    ],
+    loot: &[
+        Item::Artifact { name: String::new(), value: 0 },
+        Item::Crossbow,
+        Item::Shield,
+    ],
+};
@@ -0,0 +1,102 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::entity::Entity;
+
+//parameters for attack rolls, all in percent
+pub const BASE_HIT_CHANCE: i32 = 80;
+pub const MIN_HIT_CHANCE: i32 = 5;
+pub const MAX_HIT_CHANCE: i32 = 95;
+pub const CRITICAL_CHANCE: i32 = 5;
+pub const CRITICAL_MULTIPLIER: i32 = 2;
+// hits that only just made the roll are glancing blows
+pub const GLANCING_MARGIN: i32 = 15;
+
+/// Extra damage rolled on top of power for each attack.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DamageRange {
+    pub min: i32,
+    pub max: i32,
+}
+
+impl DamageRange {
+    pub fn new(min: i32, max: i32) -> Self {
+        DamageRange { min, max }
+    }
+
+    pub fn roll(&self) -> i32 {
+        if self.max <= self.min {
+            return self.min;
+        }
+        rand::thread_rng().gen_range(self.min, self.max + 1)
+    }
+}
+
+impl std::ops::Add for DamageRange {
+    type Output = DamageRange;
+
+    fn add(self, other: DamageRange) -> DamageRange {
+        DamageRange::new(self.min + other.min, self.max + other.max)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttackOutcome {
+    Miss,
+    Glancing,
+    Hit,
+    Critical,
+}
+
+impl std::fmt::Display for AttackOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            AttackOutcome::Miss => write!(f, "miss"),
+            AttackOutcome::Glancing => write!(f, "glancing"),
+            AttackOutcome::Hit => write!(f, "hit"),
+            AttackOutcome::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AttackRoll {
+    pub outcome: AttackOutcome,
+    pub damage: i32,
+}
+
+pub fn hit_chance(attacker: &Entity, target: &Entity) -> i32 {
+    (BASE_HIT_CHANCE + attacker.accuracy() - target.evasion())
+        .max(MIN_HIT_CHANCE)
+        .min(MAX_HIT_CHANCE)
+}
+
+/// Rolls a melee attack to hit, then for damage. Damage is before the target's resistances are applied.
+pub fn roll_attack(attacker: &Entity, target: &Entity) -> AttackRoll {
+    roll_with_damage(attacker, target, attacker.power() + attacker.damage_range().roll())
+}
+
+/// Rolls a ranged attack, where the damage comes from the weapon rather than the attacker's strength.
+pub fn roll_ranged_attack(attacker: &Entity, target: &Entity, damage: DamageRange) -> AttackRoll {
+    roll_with_damage(attacker, target, damage.roll())
+}
+
+fn roll_with_damage(attacker: &Entity, target: &Entity, damage: i32) -> AttackRoll {
+    let hit_chance = hit_chance(attacker, target);
+    let roll = rand::thread_rng().gen_range(0, 100);
+    resolve_attack(roll, hit_chance, damage, target.defense())
+}
+
+/// Works out what a percentile `roll` against `hit_chance` amounts to, given the damage rolled and the target's defense.
+pub fn resolve_attack(roll: i32, hit_chance: i32, damage: i32, defense: i32) -> AttackRoll {
+    if roll >= hit_chance {
+        AttackRoll { outcome: AttackOutcome::Miss, damage: 0 }
+    } else if roll < CRITICAL_CHANCE {
+        AttackRoll { outcome: AttackOutcome::Critical, damage: (damage * CRITICAL_MULTIPLIER - defense).max(1) }
+    } else if roll >= hit_chance - GLANCING_MARGIN || damage <= defense {
+        // a blow that barely lands, or that can't get through armour, still grazes
+        AttackRoll { outcome: AttackOutcome::Glancing, damage: ((damage - defense) / 2).max(1) }
+    } else {
+        AttackRoll { outcome: AttackOutcome::Hit, damage: damage - defense }
+    }
+}
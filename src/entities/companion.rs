@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::awareness::{AiState, Awareness, DEFAULT_SIGHT_RADIUS};
+use crate::entities::entity::Entity;
+use crate::entities::faction::Faction;
+use crate::game_engine::{GameEngine, PLAYER};
+
+// how far from the player a following companion will go after something
+pub const COMPANION_LEASH: i32 = 6;
+// companions further than this from the player when they take the stairs get left behind
+pub const COMPANION_TRAVEL_RANGE: i32 = 5;
+
+/// What the player has told a companion to do.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CompanionOrder {
+    /// Sticks close, only breaking off to fight things near the player.
+    Follow,
+    /// Holds its position and only fights what comes within reach.
+    Stay,
+    /// Goes after anything hostile it sees, and comes back to the player once it's done.
+    Attack,
+}
+
+impl std::fmt::Display for CompanionOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use CompanionOrder::*;
+        match *self {
+            Follow => write!(f, "Follow me"),
+            Stay => write!(f, "Stay here"),
+            Attack => write!(f, "Attack at will"),
+        }
+    }
+}
+
+/// Marks an entity as fighting on the player's side.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Companion {
+    pub order: CompanionOrder,
+}
+
+/// Turns the entity into one of the player's companions, whatever side it was on before.
+pub fn recruit(entity: &mut Entity) {
+    entity.companion = Some(Companion { order: CompanionOrder::Follow });
+    entity.faction = Some(Faction::Player);
+    entity.group = None;
+    let mut awareness = entity.awareness.unwrap_or_else(|| Awareness::new(DEFAULT_SIGHT_RADIUS));
+    awareness.forget_target();
+    awareness.provoked_by = None;
+    awareness.wander_target = None;
+    awareness.state = AiState::Wandering;
+    entity.awareness = Some(awareness);
+}
+
+// gives every living companion the same order, returning how many there were to hear it
+pub fn order_companions(order: CompanionOrder, entities: &mut [Entity]) -> usize {
+    let mut count = 0;
+    for companion in entities.iter_mut().filter(|e| e.alive).filter_map(|e| e.companion.as_mut()) {
+        companion.order = order;
+        count += 1;
+    }
+    count
+}
+
+/// Hands the xp companions have earned over to the player, who gets the credit for their kills.
+pub fn share_companion_xp(game: &mut GameEngine) {
+    let mut earned = 0;
+    for entity in game.entities.iter_mut().filter(|e| e.companion.is_some()) {
+        if let Some(fighter) = entity.fighter.as_mut() {
+            earned += fighter.xp;
+            fighter.xp = 0;
+        }
+    }
+    if earned > 0 {
+        game.entities[PLAYER].gain_xp(earned);
+    }
+}
@@ -0,0 +1,78 @@
+use std::ops::Add;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Lightning,
+    Cold,
+    Necrotic,
+}
+
+impl std::fmt::Display for DamageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            DamageType::Physical => write!(f, "physical"),
+            DamageType::Fire => write!(f, "fire"),
+            DamageType::Lightning => write!(f, "lightning"),
+            DamageType::Cold => write!(f, "cold"),
+            DamageType::Necrotic => write!(f, "necrotic"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Damage {
+    pub amount: i32,
+    pub damage_type: DamageType,
+}
+
+impl Damage {
+    pub fn new(amount: i32, damage_type: DamageType) -> Self {
+        Damage { amount, damage_type }
+    }
+}
+
+/// Percentage of each type of damage that is shrugged off. Negative values are vulnerabilities,
+/// and anything at 100 or above is an immunity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Resistances {
+    pub physical: i32,
+    pub fire: i32,
+    pub lightning: i32,
+    pub cold: i32,
+    pub necrotic: i32,
+}
+
+impl Resistances {
+    pub fn get(&self, damage_type: DamageType) -> i32 {
+        match damage_type {
+            DamageType::Physical => self.physical,
+            DamageType::Fire => self.fire,
+            DamageType::Lightning => self.lightning,
+            DamageType::Cold => self.cold,
+            DamageType::Necrotic => self.necrotic,
+        }
+    }
+
+    pub fn apply(&self, damage: Damage) -> i32 {
+        let resistance = self.get(damage.damage_type).min(100);
+        damage.amount * (100 - resistance) / 100
+    }
+}
+
+impl Add for Resistances {
+    type Output = Resistances;
+
+    fn add(self, other: Resistances) -> Resistances {
+        Resistances {
+            physical: self.physical + other.physical,
+            fire: self.fire + other.fire,
+            lightning: self.lightning + other.lightning,
+            cold: self.cold + other.cold,
+            necrotic: self.necrotic + other.necrotic,
+        }
+    }
+}
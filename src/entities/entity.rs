@@ -1,23 +1,54 @@
 use core::option::Option;
 use core::option::Option::{None, Some};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use bracket_lib::prelude::to_cp437;
 use bracket_lib::terminal::Console;
+use rand::Rng;
 // use tcod::colors::{Color, LIGHT_GREEN, LIGHT_YELLOW, RED};
 // use tcod::console::{BackgroundFlag, Console};
 use serde::{Deserialize, Serialize};
 use crate::Messages;
+use crate::entities::awareness::Awareness;
+use crate::entities::companion::Companion;
+use crate::entities::combat::{AttackOutcome, AttackRoll, DamageRange, roll_attack, roll_ranged_attack};
+use crate::entities::damage::{Damage, DamageType, Resistances};
 use crate::entities::equipment::Equipment;
+use crate::entities::faction::{Faction, Relationship};
 use crate::entities::fighter::Fighter;
+use crate::entities::group::GroupMembership;
+use crate::entities::hunger::Hunger;
+use crate::entities::status_effect::{StatusEffect, StatusEffectKind};
 use crate::events::game_event_processing::{EventBus, EventData, EventType, GameEvent};
 use crate::graphics::camera::Camera;
 use crate::items::item::Item;
+use crate::items::shop::Shop;
+use crate::map::lighting::LightSource;
 use crate::util::ai::Ai;
 
+/// The energy it takes to act once. Every round an entity gains energy equal to its speed,
+/// and acts for as long as it has enough saved up.
+pub const ACTION_COST: i32 = 12;
+pub const NORMAL_SPEED: i32 = 12;
+
+// hands out the ids that keep pointing at the same entity however the entity list gets shuffled around
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Makes sure entities created from now on don't reuse any of these entities' ids, e.g. after loading a game.
+pub fn reserve_ids(entities: &[Entity]) {
+    let next = entities.iter()
+        .flat_map(|e| std::iter::once(e).chain(e.inventory.iter()))
+        .map(|e| e.id + 1)
+        .max()
+        .unwrap_or(0);
+    NEXT_ID.fetch_max(next, Ordering::Relaxed);
+}
+
 /// This is a generic object: the player, a monster, an item, the stairs...
 /// It's always represented by a character on screen.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Entity {
+    pub id: usize,
     pub x: i32,
     pub y: i32,
     pub char: char,
@@ -27,16 +58,29 @@ pub struct Entity {
     pub alive: bool,
     pub fighter: Option<Fighter>,
     pub ai: Option<Ai>,
+    pub awareness: Option<Awareness>,
+    pub group: Option<GroupMembership>,
+    pub faction: Option<Faction>,
+    pub companion: Option<Companion>,
+    pub hunger: Option<Hunger>,
     pub item: Option<Item>,
+    // how many of the item this is, for the ones that stack
+    pub quantity: i32,
     pub always_visible: bool,
+    pub light: Option<LightSource>,
     pub level: i32,
     pub equipment: Option<Equipment>,
     pub inventory: Vec<Entity>,
+    pub gold: i32,
+    pub shop: Option<Shop>,
+    pub status_effects: Vec<StatusEffect>,
+    pub energy: i32,
 }
 
 impl Entity {
     pub fn new(x: i32, y: i32, char: char, color: Color, name: &str, blocks: bool) -> Self {
         Entity {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
             x: x,
             y: y,
             char: char,
@@ -46,11 +90,22 @@ impl Entity {
             alive: false,
             fighter: None,
             ai: None,
+            awareness: None,
+            group: None,
+            faction: None,
+            companion: None,
+            hunger: None,
             item: None,
+            quantity: 1,
             always_visible: false,
+            light: None,
             level: 1,
             equipment: None,
-            inventory: vec![]
+            inventory: vec![],
+            gold: 0,
+            shop: None,
+            status_effects: vec![],
+            energy: ACTION_COST,
         }
     }
 
@@ -78,13 +133,27 @@ impl Entity {
         self.y = y;
     }
 
+    /// Whether this item can share an inventory slot with the other one.
+    pub fn stacks_with(&self, other: &Entity) -> bool {
+        match self.item.as_ref() {
+            Some(item) => item.is_stackable() && other.item.as_ref() == Some(item) && self.name == other.name,
+            None => false,
+        }
+    }
+
     pub fn distance_to(&self, other: &Entity) -> f32 {
         let dx = other.x - self.x;
         let dy = other.y - self.y;
         ((dx.pow(2) + dy.pow(2)) as f32).sqrt()
     }
 
-    pub fn take_damage(&mut self, damage: i32, event_bus: &mut EventBus) -> Option<i32>{
+    /// Applies damage dealt by `source`, if anything dealt it, after this entity's resistances. Whoever lands
+    /// the killing blow is credited with this entity's xp reward, which is also returned.
+    pub fn take_damage(&mut self, damage: Damage, source: Option<&mut Entity>, event_bus: &mut EventBus) -> Option<i32>{
+        if let Some(source) = source.as_ref() {
+            self.provoke(source);
+        }
+        let damage = self.damage_after_resistances(damage);
         if let Some(fighter) = self.fighter.as_mut() {
             if damage > 0 {
                 fighter.hp -= damage;
@@ -94,19 +163,55 @@ impl Entity {
             if fighter.hp <= 0 {
                 self.alive = false;
                 fighter.on_death.callback(self, event_bus);
-                return Some(fighter.xp);
+                if let Some(source) = source {
+                    source.gain_xp(fighter.xp_reward);
+                }
+                return Some(fighter.xp_reward);
             }
         }
         None
     }
 
-    pub fn attack(&mut self, target: &mut Entity, event_bus: &mut EventBus) {
-        let damage = self.power() - target.defense();
+    pub fn gain_xp(&mut self, xp: i32) {
+        if let Some(fighter) = self.fighter.as_mut() {
+            fighter.xp += xp;
+        }
+    }
+
+    pub fn attack(&mut self, target: &mut Entity, event_bus: &mut EventBus) -> AttackOutcome {
+        let damage_type = self.fighter.map_or(DamageType::Physical, |f| f.damage_type);
+        let roll = roll_attack(self, target);
+        let outcome = self.resolve_attack(target, roll, damage_type, event_bus);
+        if outcome != AttackOutcome::Miss && target.alive {
+            self.inflict_on_hit_effects(target);
+        }
+        outcome
+    }
+
+    // every piece of gear with an on-hit effect gets its own chance to inflict it, and the wielder
+    // gets the credit if the poison or burn finishes the target off
+    fn inflict_on_hit_effects(&self, target: &mut Entity) {
+        for on_hit in self.get_all_equipped().iter().filter_map(|e| e.on_hit) {
+            if rand::thread_rng().gen_range(0, 100) < on_hit.chance {
+                target.add_status_effect(StatusEffect::new(on_hit.effect, on_hit.turns, on_hit.magnitude).with_source(self.id));
+            }
+        }
+    }
+
+    pub fn shoot(&mut self, target: &mut Entity, damage: DamageRange, event_bus: &mut EventBus) -> AttackOutcome {
+        let roll = roll_ranged_attack(self, target, damage);
+        self.resolve_attack(target, roll, DamageType::Physical, event_bus)
+    }
+
+    fn resolve_attack(&mut self, target: &mut Entity, roll: AttackRoll, damage_type: DamageType, event_bus: &mut EventBus) -> AttackOutcome {
+        // even a miss is enough to start a fight
+        target.provoke(self);
+        let raw_damage = Damage::new(roll.damage, damage_type);
+        let damage = target.damage_after_resistances(raw_damage);
         let mut killed = false;
         if damage > 0 {
             // game.messages.add(format!("{} attacks {} for {} hit points", self.name, target.name, damage), WHITE);
-            if let Some(xp) = target.take_damage(damage, event_bus) {
-                self.fighter.as_mut().unwrap().xp += xp;
+            if target.take_damage(raw_damage, Some(&mut *self), event_bus).is_some() {
                 killed = true;
             }
         } else {
@@ -120,9 +225,46 @@ impl Entity {
                 ("attacker_name".to_string(), EventData::String(self.name.clone())),
                 ("attacker_pos".to_string(), EventData::TupleI32I32(self.pos())),
                 ("damage".to_string(), EventData::I32(damage)),
+                ("damage_type".to_string(), EventData::String(damage_type.to_string())),
                 ("killed".to_string(), EventData::Boolean(killed)),
+                ("outcome".to_string(), EventData::String(roll.outcome.to_string())),
+                ("hit".to_string(), EventData::Boolean(roll.outcome != AttackOutcome::Miss)),
+                ("critical".to_string(), EventData::Boolean(roll.outcome == AttackOutcome::Critical)),
+                ("glancing".to_string(), EventData::Boolean(roll.outcome == AttackOutcome::Glancing)),
             ])
-        ))
+        ));
+        roll.outcome
+    }
+
+    /// How this entity and the other get along. Factions set the baseline, but a neutral that has been
+    /// attacked holds a grudge against the attacker's whole faction.
+    pub fn relationship_to(&self, other: &Entity) -> Relationship {
+        match (self.faction, other.faction) {
+            (Some(faction), Some(other_faction)) => {
+                let grudge = self.awareness.and_then(|a| a.provoked_by) == Some(other_faction)
+                    || other.awareness.and_then(|a| a.provoked_by) == Some(faction);
+                if grudge {
+                    Relationship::Hostile
+                } else {
+                    faction.relationship(other_faction)
+                }
+            }
+            _ => Relationship::Neutral,
+        }
+    }
+
+    pub fn is_hostile_to(&self, other: &Entity) -> bool {
+        self.relationship_to(other) == Relationship::Hostile
+    }
+
+    // a neutral that gets attacked turns on the attacker's faction; allies squabbling by accident don't count
+    pub fn provoke(&mut self, attacker: &Entity) {
+        if self.relationship_to(attacker) != Relationship::Neutral {
+            return;
+        }
+        if let (Some(awareness), Some(faction)) = (self.awareness.as_mut(), attacker.faction) {
+            awareness.provoked_by = Some(faction);
+        }
     }
 
     pub fn heal(&mut self, amount: i32) {
@@ -161,6 +303,7 @@ impl Entity {
         }
         if let Some(ref mut equipment) = self.equipment {
             if equipment.equipped {
+                equipment.equipped = false;
                 messages.add(format!("Unequipped {} from {}.", self.name, equipment.slot), LIGHT_YELLOW);
             }
         } else {
@@ -171,7 +314,9 @@ impl Entity {
     pub fn power(&self) -> i32 {
         let base_power = self.fighter.map_or(0, |f| f.base_power);
         let bonus: i32 = self.get_all_equipped().iter().map(|e| e.power_bonus).sum();
-        base_power + bonus
+        let weakness = self.get_status_effect(StatusEffectKind::Weakened).map_or(0, |e| e.magnitude);
+        let hunger = self.hunger.map_or(0, |h| h.status().power_penalty());
+        base_power + bonus - weakness - hunger
     }
 
     pub fn defense(&self) -> i32 {
@@ -180,12 +325,71 @@ impl Entity {
         base_defense + bonus
     }
 
+    pub fn resistances(&self) -> Resistances {
+        let base_resistances = self.fighter.map_or(Resistances::default(), |f| f.resistances);
+        self.get_all_equipped().iter().fold(base_resistances, |total, e| total + e.resistances)
+    }
+
+    pub fn damage_after_resistances(&self, damage: Damage) -> i32 {
+        self.resistances().apply(damage)
+    }
+
+    pub fn accuracy(&self) -> i32 {
+        let hunger = self.hunger.map_or(0, |h| h.status().accuracy_penalty());
+        self.fighter.map_or(0, |f| f.accuracy) - hunger
+    }
+
+    pub fn evasion(&self) -> i32 {
+        self.fighter.map_or(0, |f| f.evasion)
+    }
+
+    pub fn stealth(&self) -> i32 {
+        self.fighter.map_or(0, |f| f.stealth)
+    }
+
+    // how much clanking the entity's gear adds to everything it does
+    pub fn equipment_noise(&self) -> i32 {
+        self.get_all_equipped().iter().map(|e| e.noise).sum()
+    }
+
+    pub fn damage_range(&self) -> DamageRange {
+        let base_damage = self.fighter.map_or(DamageRange::default(), |f| f.damage);
+        self.get_all_equipped().iter().fold(base_damage, |total, e| total + e.damage)
+    }
+
     pub fn max_hp(&self) -> i32 {
         let base_max_hp = self.fighter.map_or(0, |f| f.base_max_hp);
         let bonus: i32 = self.get_all_equipped().iter().map(|e| e.max_hp_bonus).sum();
         base_max_hp + bonus
     }
 
+    pub fn add_status_effect(&mut self, effect: StatusEffect) {
+        match self.status_effects.iter_mut().find(|e| e.kind == effect.kind) {
+            Some(existing) => existing.stack_with(effect),
+            None => self.status_effects.push(effect),
+        }
+    }
+
+    pub fn get_status_effect(&self, kind: StatusEffectKind) -> Option<&StatusEffect> {
+        self.status_effects.iter().find(|e| e.kind == kind)
+    }
+
+    pub fn has_status_effect(&self, kind: StatusEffectKind) -> bool {
+        self.get_status_effect(kind).is_some()
+    }
+
+    // how much energy this entity gains each round
+    pub fn speed(&self) -> i32 {
+        use StatusEffectKind::*;
+        if self.has_status_effect(Hasted) {
+            NORMAL_SPEED * 2
+        } else if self.has_status_effect(Slowed) {
+            NORMAL_SPEED / 2
+        } else {
+            NORMAL_SPEED
+        }
+    }
+
     pub fn get_all_equipped(&self) -> Vec<Equipment>{
         self.inventory.iter()
             .filter(|e| e.equipment.map_or(false, |e| e.equipped))
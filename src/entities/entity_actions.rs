@@ -1,18 +1,35 @@
 use std::borrow::BorrowMut;
 use std::collections::HashMap;
 
-use tcod::colors::{GREEN, RED};
+use rand::Rng;
+use tcod::colors::{GOLD, GREEN, LIGHT_GREY, RED};
 
 use crate::{MAP_HEIGHT, MAP_WIDTH};
+use crate::entities::damage::{Damage, DamageType};
 use crate::entities::entity::Entity;
-use crate::events::game_event_processing::{EventData, EventType, GameEvent};
+use crate::entities::status_effect::{StatusEffect, StatusEffectKind};
+use crate::events::game_event_processing::{EventBus, EventData, EventType, GameEvent};
 use crate::framework::Tcod;
-use crate::game_engine::{GameEngine, PLAYER};
-use crate::inventory::inventory_actions::get_equipped_id_in_slot;
+use crate::game_engine::{CONFIRM_SCREEN_WIDTH, GameEngine, PLAYER};
+use crate::graphics::render_functions::menu;
+use crate::inventory::inventory_actions::{add_to_inventory, get_equipped_id_in_slot};
+use crate::inventory::shop_screen::shop_screen;
+use crate::items::item::Item;
 use crate::map::map_functions::is_blocked;
 use crate::map::mapgen::Map;
+use crate::map::noise::{action_noise, NOISE_ATTACK, NOISE_MOVE};
 use crate::util::mut_two::mut_two;
 
+pub const DIRECTIONS: [(i32, i32); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1, 0), (1, 0),
+    (-1, 1), (0, 1), (1, 1),
+];
+
+pub fn random_direction() -> (i32, i32) {
+    DIRECTIONS[rand::thread_rng().gen_range(0, DIRECTIONS.len())]
+}
+
 pub fn move_by(id: usize, dx: i32, dy: i32, map: &Map, entity: &mut [Entity]) {
     let (x,y) = entity[id].pos();
     if !is_blocked(x + dx, y + dy, map, entity) {
@@ -20,42 +37,171 @@ pub fn move_by(id: usize, dx: i32, dy: i32, map: &Map, entity: &mut [Entity]) {
     }
 }
 
-pub fn player_move_or_attack(dx: i32, dy: i32, game: &mut GameEngine) {
+/// Moves the player, or attacks whatever is in the way. Bumping into a shopkeeper who hasn't been provoked
+/// opens their shop instead. Returns false if no turn was taken: the player thought better of attacking
+/// something that wasn't hostile, or left a shop without trading.
+pub fn player_move_or_attack(dx: i32, dy: i32, tcod: &mut Tcod, game: &mut GameEngine) -> bool {
+    // a confused player only goes where they mean to half the time
+    let (dx, dy) = if game.entities[PLAYER].has_status_effect(StatusEffectKind::Confused) && rand::random() {
+        random_direction()
+    } else {
+        (dx, dy)
+    };
     let x = game.entities[PLAYER].x + dx;
     let y = game.entities[PLAYER].y + dy;
 
+    let target_id = game.entities.iter().position(|entity| entity.fighter.is_some() && entity.pos() == (x,y));    // position() is an iterator function. It returns the position of the first to match the criteria
+    if let Some(target_id) = target_id {
+        if game.entities[target_id].companion.is_some() {
+            // companions step aside rather than get hit
+            let (player_x, player_y) = game.entities[PLAYER].pos();
+            game.entities[target_id].set_pos(player_x, player_y);
+            game.entities[PLAYER].set_pos(x, y);
+            let player = &game.entities[PLAYER];
+            let event = noisy_event(EventType::PlayerMove, player.pos(), action_noise(player, NOISE_MOVE));
+            game.add_event(event);
+            return true;
+        }
+        let target = &game.entities[target_id];
+        if target.shop.is_some() && !game.entities[PLAYER].is_hostile_to(target) {
+            return shop_screen(target_id, tcod, game);
+        }
+        if !game.entities[PLAYER].is_hostile_to(target) {
+            let question = format!("The {} means you no harm. Attack it anyway?", target.name);
+            if menu(&question, &["Yes", "No"], CONFIRM_SCREEN_WIDTH, &mut tcod.root) != Some(0) {
+                return false;
+            }
+        }
+    }
+
     let map: &Map = &game.map;
     let entities: &mut Vec<Entity> = game.entities.borrow_mut();
     let event_bus = game.event_bus.borrow_mut();
 
-    let target_id = entities.iter().position(|entity| entity.fighter.is_some() && entity.pos() == (x,y));    // position() is an iterator function. It returns the position of the first to match the criteria
     match target_id {
         Some(target_id) => {
             let (player, target) = mut_two(PLAYER, target_id, game.entities.borrow_mut());
             player.attack(target, event_bus);
-            event_bus.add_event(GameEvent::from_type(EventType::PlayerAttack));
+            event_bus.add_event(noisy_event(EventType::PlayerAttack, player.pos(), action_noise(player, NOISE_ATTACK)));
         }
         None => {
             move_by(PLAYER, dx, dy, map, entities);
-            event_bus.add_event(GameEvent::from_type(EventType::PlayerMove));
+            let player = &entities[PLAYER];
+            event_bus.add_event(noisy_event(EventType::PlayerMove, player.pos(), action_noise(player, NOISE_MOVE)));
         }
     }
+    true
 }
 
-pub fn pick_item_up(object_id: usize, game: &mut GameEngine) {
-    if game.entities[PLAYER].inventory.len() >= 26 {
-        game.messages.add(format!("Your pickets are full - you can't pickup the {}", game.entities[object_id].name), RED)
+// an event that monsters can hear, `noise` tiles away from `position`
+pub fn noisy_event(event_type: EventType, position: (i32, i32), noise: i32) -> GameEvent {
+    GameEvent::from_type_with_data(
+        event_type,
+        HashMap::from([
+            ("position".to_string(), EventData::TupleI32I32(position)),
+            ("noise".to_string(), EventData::I32(noise)),
+        ])
+    )
+}
+
+/// Damages the target on behalf of the source entity, so the source gets the credit for a kill.
+/// Returns the xp the target was worth if it died.
+pub fn deal_damage(target_id: usize, source_id: Option<usize>, damage: Damage, entities: &mut [Entity], event_bus: &mut EventBus) -> Option<i32> {
+    match source_id {
+        Some(source_id) if source_id != target_id => {
+            let (target, source) = mut_two(target_id, source_id, entities);
+            target.take_damage(damage, Some(source), event_bus)
+        }
+        _ => entities[target_id].take_damage(damage, None, event_bus)
+    }
+}
+
+/// Advances the entity's status effects by a turn: poison and burning hurt, regeneration heals,
+/// and effects that have run out are removed.
+pub fn tick_status_effects(id: usize, game: &mut GameEngine) {
+    use StatusEffectKind::*;
+    let (x, y) = game.entities[id].pos();
+    let visible = game.is_visible(x, y);
+    let entities: &mut Vec<Entity> = game.entities.borrow_mut();
+    let event_bus = game.event_bus.borrow_mut();
+    let effects = entities[id].status_effects.clone();
+    for effect in effects {
+        match effect.kind {
+            Poisoned => {
+                let source = living_source(effect, entities);
+                deal_damage(id, source, Damage::new(effect.magnitude, DamageType::Necrotic), entities, event_bus);
+            }
+            Burning => {
+                let source = living_source(effect, entities);
+                deal_damage(id, source, Damage::new(effect.magnitude, DamageType::Fire), entities, event_bus);
+            }
+            Regenerating => entities[id].heal(effect.magnitude),
+            _ => {}
+        }
+    }
+    if !entities[id].alive {
+        entities[id].status_effects.clear();
+        return;
+    }
+
+    let entity = &mut entities[id];
+    entity.status_effects.iter_mut().for_each(|e| e.turns_remaining -= 1);
+    let (expired, remaining): (Vec<StatusEffect>, Vec<StatusEffect>) = entity.status_effects
+        .drain(..)
+        .partition(|e| e.turns_remaining <= 0);
+    entity.status_effects = remaining;
+    for effect in expired {
+        if id == PLAYER {
+            game.messages.add(format!("You are no longer {}.", effect.kind), LIGHT_GREY);
+        } else if visible {
+            game.messages.add(format!("The {} is no longer {}.", entity.name, effect.kind), LIGHT_GREY);
+        }
+        event_bus.add_event(GameEvent::from_type_with_data(
+            EventType::StatusEffectExpired,
+            HashMap::from([
+                ("entity_name".to_string(), EventData::String(entity.name.clone())),
+                ("effect".to_string(), EventData::String(effect.kind.to_string())),
+            ])
+        ));
+    }
+}
+
+// whoever inflicted the effect, as long as they're still around to take the credit for it
+fn living_source(effect: StatusEffect, entities: &[Entity]) -> Option<usize> {
+    effect.source.and_then(|source_id| entities.iter().position(|e| e.id == source_id && e.alive))
+}
+
+/// Picks up `count` items off the pile on the floor; asking for the whole pile or more takes all of it.
+pub fn pick_item_up(object_id: usize, count: i32, game: &mut GameEngine) {
+    // gold goes straight in the purse, and there's always room for more
+    if game.entities[object_id].item == Some(Item::Gold) {
+        let gold = game.entities.swap_remove(object_id);
+        game.entities[PLAYER].gold += gold.quantity;
+        game.messages.add(format!("You pick up {} gold.", gold.quantity), GOLD);
+        return;
+    }
+    let has_stack = game.entities[PLAYER].inventory.iter().any(|item| item.stacks_with(&game.entities[object_id]));
+    if game.entities[PLAYER].inventory.len() >= 26 && !has_stack {
+        game.messages.add(format!("Your pickets are full - you can't pickup the {}", game.identification.describe(&game.entities[object_id])), RED)
     }
     else {
-        let item = game.entities.swap_remove(object_id);
-        game.messages.add(format!("You picked up the {}", item.name), GREEN);
+        let item = if count < game.entities[object_id].quantity {
+            let pile = &mut game.entities[object_id];
+            pile.quantity -= count;
+            let mut taken = pile.clone();
+            taken.quantity = count;
+            taken
+        } else {
+            game.entities.swap_remove(object_id)
+        };
+        let name = game.identification.describe(&item);
+        game.messages.add(format!("You picked up the {}", name), GREEN);
         game.add_event(GameEvent::from_type_with_data(
             EventType::PlayerPickupItem,
-            HashMap::from([("item".to_string(), EventData::String(item.name.clone()))])
+            HashMap::from([("item".to_string(), EventData::String(name))])
         ));
-        let index = game.entities[PLAYER].inventory.len();
         let slot = item.equipment.map(|e| e.slot);
-        game.entities[PLAYER].inventory.push(item);
+        let index = add_to_inventory(&mut game.entities[PLAYER].inventory, item);
 
         // equip picked up item if it is equipment and the slot is open
         if let Some(slot) = slot {
@@ -77,6 +223,26 @@ pub fn move_towards(id: usize, target_x: i32, target_y: i32, map: &Map, entities
     move_by(id, dx, dy, map, entities);
 }
 
+/// Steps onto whichever free neighbouring tile is furthest from the given position.
+/// Returns false if there was nowhere to go that would put more distance between them.
+pub fn move_away_from(id: usize, from_x: i32, from_y: i32, map: &Map, entities: &mut [Entity]) -> bool {
+    let (x, y) = entities[id].pos();
+    let current_distance = entities[id].distance(from_x, from_y);
+    let escape = DIRECTIONS.iter()
+        .map(|&(dx, dy)| (x + dx, y + dy))
+        .filter(|&(new_x, new_y)| !is_blocked(new_x, new_y, map, entities))
+        .map(|(new_x, new_y)| (new_x, new_y, (((new_x - from_x).pow(2) + (new_y - from_y).pow(2)) as f32).sqrt()))
+        .filter(|&(_, _, distance)| distance > current_distance)
+        .max_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+    match escape {
+        Some((new_x, new_y, _)) => {
+            entities[id].set_pos(new_x, new_y);
+            true
+        }
+        None => false
+    }
+}
+
 pub fn target_tile(
     tcod: &mut Tcod,
     game: &mut GameEngine,
@@ -95,7 +261,7 @@ pub fn target_tile(
         game.render_all(tcod, false);
         let (x, y) = (tcod.mouse.cx as i32, tcod.mouse.cy as i32);
 
-        let in_fov = (x < MAP_WIDTH) && (y < MAP_HEIGHT) && tcod.fov.is_in_fov(x, y);
+        let in_fov = (x < MAP_WIDTH) && (y < MAP_HEIGHT) && game.is_visible(x, y);
         let in_range = max_range.map_or(true, |range| game.entities[PLAYER].distance(x, y) <= range);
         if tcod.mouse.lbutton_pressed && in_fov && in_range {
             return Some((x, y))
@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
+use crate::entities::combat::DamageRange;
+use crate::entities::damage::Resistances;
+use crate::entities::ranged::RangedWeapon;
 use crate::entities::slot::Slot;
+use crate::entities::status_effect::StatusEffectKind;
+use crate::items::affix::Rarity;
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Equipment {
@@ -8,4 +13,19 @@ pub struct Equipment {
     pub max_hp_bonus: i32,
     pub power_bonus: i32,
     pub defense_bonus: i32,
-}
\ No newline at end of file
+    pub damage: DamageRange,
+    pub resistances: Resistances,
+    pub ranged: Option<RangedWeapon>,
+    pub noise: i32,
+    pub rarity: Rarity,
+    pub on_hit: Option<OnHit>,
+}
+
+/// A status effect a weapon has a `chance` in percent of inflicting whenever it lands a blow.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OnHit {
+    pub effect: StatusEffectKind,
+    pub chance: i32,
+    pub turns: i32,
+    pub magnitude: i32,
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// Who an entity sides with. Monsters fight anything their faction is hostile to, the player included.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Faction {
+    Player,
+    Greenskins,
+    Undead,
+    Wildlife,
+    // shopkeepers, who'll trade with anyone
+    Merchant,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Relationship {
+    Hostile,
+    Neutral,        // leaves the other alone unless provoked
+    Allied,
+}
+
+impl Faction {
+    pub fn relationship(self, other: Faction) -> Relationship {
+        use Faction::*;
+        if self == other {
+            return Relationship::Allied;
+        }
+        match (self, other) {
+            (Wildlife, _) | (_, Wildlife) | (Merchant, _) | (_, Merchant) => Relationship::Neutral,
+            _ => Relationship::Hostile,
+        }
+    }
+}
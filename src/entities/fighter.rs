@@ -1,13 +1,21 @@
 use serde::{Deserialize, Serialize};
 use crate::DeathCallback;
+use crate::entities::combat::DamageRange;
+use crate::entities::damage::{DamageType, Resistances};
 
-//TODO spilt xp store for player and drop xp into different values
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Fighter {
     pub base_max_hp: i32,
     pub hp: i32,
     pub base_defense: i32,
     pub base_power: i32,
-    pub xp: i32,
+    pub accuracy: i32,
+    pub evasion: i32,
+    pub stealth: i32,       // how much quieter than usual this fighter goes about its business
+    pub damage: DamageRange,
+    pub xp: i32,            // experience this fighter has earned
+    pub xp_reward: i32,     // experience awarded to whoever kills this fighter
+    pub damage_type: DamageType,  // the kind of damage this fighter's attacks deal
+    pub resistances: Resistances,
     pub on_death: DeathCallback
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::monster::Monster;
+use crate::util::transition::Transition;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GroupRole {
+    Leader,
+    Member,
+}
+
+/// Marks a monster as part of a pack. Monsters belong to the same pack when they share a group id.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GroupMembership {
+    pub group_id: u32,
+    pub role: GroupRole,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct GroupMember {
+    pub monster: Monster,
+    pub min: i32,
+    pub max: i32,
+}
+
+/// A pack of monsters that spawns together: a leader, and between `min` and `max` of each kind of follower.
+/// `chance` is how likely the pack is to be picked, by dungeon level.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct GroupTemplate {
+    pub name: String,
+    pub leader: Monster,
+    pub members: Vec<GroupMember>,
+    pub chance: Vec<Transition>,
+}
+
+pub const GROUP_TEMPLATES_PATH: &str = "assets/data/groups.json";
+
+lazy_static! {
+    pub static ref GROUP_TEMPLATES: Vec<GroupTemplate> = load_group_templates();
+}
+
+/// Reads the pack definitions from the data file. Without them the dungeon simply has no packs.
+pub fn load_group_templates() -> Vec<GroupTemplate> {
+    let templates = std::fs::read_to_string(GROUP_TEMPLATES_PATH)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+    match templates {
+        Ok(templates) => templates,
+        Err(e) => {
+            log::error!("could not load group templates from {}: {}", GROUP_TEMPLATES_PATH, e);
+            vec![]
+        }
+    }
+}
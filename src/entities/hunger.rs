@@ -0,0 +1,143 @@
+use std::borrow::BorrowMut;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::Color;
+use tcod::colors::{DARK_RED, LIGHT_GREY, ORANGE, RED, YELLOW};
+
+use crate::GAME_CONFIGS;
+use crate::entities::damage::{Damage, DamageType};
+use crate::entities::entity::Entity;
+use crate::entities::status_effect::{StatusEffect, StatusEffectKind};
+use crate::game_engine::{GameEngine, PLAYER};
+
+// how long a faint lasts
+pub const FAINT_TURNS: i32 = 3;
+// you can't stomach anything more once you're this full, in percent
+pub const FULL_PERCENT: i32 = 90;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum HungerStatus {
+    Fed,
+    Hungry,
+    Weak,
+    Fainting,
+    Starving,
+}
+
+impl HungerStatus {
+    pub fn color(self) -> Color {
+        use HungerStatus::*;
+        match self {
+            Fed => LIGHT_GREY,
+            Hungry => YELLOW,
+            Weak => ORANGE,
+            Fainting => RED,
+            Starving => DARK_RED,
+        }
+    }
+
+    pub fn accuracy_penalty(self) -> i32 {
+        use HungerStatus::*;
+        match self {
+            Fed => 0,
+            Hungry => 5,
+            Weak => 10,
+            Fainting | Starving => 15,
+        }
+    }
+
+    pub fn power_penalty(self) -> i32 {
+        use HungerStatus::*;
+        match self {
+            Fed | Hungry => 0,
+            Weak => 1,
+            Fainting | Starving => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for HungerStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use HungerStatus::*;
+        match *self {
+            Fed => write!(f, "fed"),
+            Hungry => write!(f, "hungry"),
+            Weak => write!(f, "weak"),
+            Fainting => write!(f, "fainting"),
+            Starving => write!(f, "starving"),
+        }
+    }
+}
+
+/// How much food an entity has in it. It burns down every turn, and eating tops it back up to `max`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Hunger {
+    pub satiation: i32,
+    pub max: i32,
+}
+
+impl Hunger {
+    pub fn new(max: i32) -> Self {
+        Hunger { satiation: max, max }
+    }
+
+    pub fn status(&self) -> HungerStatus {
+        let config = &GAME_CONFIGS.hunger;
+        match self.satiation {
+            s if s <= 0 => HungerStatus::Starving,
+            s if s < config.fainting_below => HungerStatus::Fainting,
+            s if s < config.weak_below => HungerStatus::Weak,
+            s if s < config.hungry_below => HungerStatus::Hungry,
+            _ => HungerStatus::Fed,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.satiation * 100 >= self.max * FULL_PERCENT
+    }
+
+    pub fn eat(&mut self, nutrition: i32) {
+        self.satiation = (self.satiation + nutrition).min(self.max);
+    }
+}
+
+/// Burns through a turn's worth of the player's food. A player who lets it run low fights worse, faints,
+/// and in the end starves.
+pub fn tick_hunger(game: &mut GameEngine) {
+    let config = &GAME_CONFIGS.hunger;
+    let entities: &mut Vec<Entity> = game.entities.borrow_mut();
+    let player = &mut entities[PLAYER];
+    let mut hunger = match player.hunger {
+        Some(hunger) => hunger,
+        None => return,
+    };
+    let before = hunger.status();
+    hunger.satiation = (hunger.satiation - config.per_turn).max(0);
+    player.hunger = Some(hunger);
+
+    let status = hunger.status();
+    if status != before {
+        let message = match status {
+            HungerStatus::Fed => None,
+            HungerStatus::Hungry => Some("You are getting hungry."),
+            HungerStatus::Weak => Some("You feel weak with hunger!"),
+            HungerStatus::Fainting => Some("You are about to faint from hunger!"),
+            HungerStatus::Starving => Some("You are starving to death!"),
+        };
+        if let Some(message) = message {
+            game.messages.add(message, status.color());
+        }
+    }
+    match status {
+        HungerStatus::Fainting if !player.has_status_effect(StatusEffectKind::Stunned)
+            && rand::thread_rng().gen_range(0, 100) < config.faint_chance => {
+            player.add_status_effect(StatusEffect::new(StatusEffectKind::Stunned, FAINT_TURNS, 0));
+            game.messages.add("You faint from hunger!", RED);
+        }
+        HungerStatus::Starving => {
+            player.take_damage(Damage::new(config.starve_damage, DamageType::Necrotic), None, game.event_bus.borrow_mut());
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// The kinds of monster the dungeon can spawn. Each one is a template that mapgen turns into an entity,
+/// complete with its stats, gear and AI.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Monster {
+    Orc,
+    OrcCaptain,
+    Kobold,
+    GoblinArcher,
+    OrcShaman,
+    CaveBeetle,
+    Troll,
+    Skeleton,
+    SkeletonCaptain,
+    Spectre,
+    // only ever hired, never found
+    Mercenary,
+}
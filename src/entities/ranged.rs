@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::colors::{LIGHT_GREY, LIGHT_VIOLET, WHITE};
+
+use crate::entities::combat::{AttackOutcome, DamageRange};
+use crate::entities::entity::Entity;
+use crate::entities::equipment::Equipment;
+use crate::events::game_event_processing::{EventData, EventType, GameEvent};
+use crate::game_engine::{GameEngine, PLAYER};
+use crate::inventory::inventory_actions::take_from_stack;
+use crate::items::identification::is_potion;
+use crate::items::item::{HEAL_AMOUNT, Item};
+use crate::map::mapgen::in_map_bounds;
+use crate::util::line::line;
+use crate::util::mut_two::mut_two;
+
+// chance in percent that a projectile breaks when it hits something
+pub const AMMO_BREAK_CHANCE: i32 = 25;
+// how far something weightless can be thrown; every two points of weight take a tile off that
+pub const THROW_RANGE: i32 = 8;
+pub const MIN_THROW_RANGE: i32 = 2;
+// a thrown potion splashes everything this close to where it breaks
+pub const POTION_SPLASH_RADIUS: i32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AmmoType {
+    Arrow,
+    Bolt,
+}
+
+impl AmmoType {
+    pub fn item(self) -> Item {
+        match self {
+            AmmoType::Arrow => Item::Arrow,
+            AmmoType::Bolt => Item::Bolt,
+        }
+    }
+}
+
+/// A weapon that attacks from a distance. Without an ammo type, the weapon itself is what gets thrown.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RangedWeapon {
+    pub range: i32,
+    pub damage: DamageRange,
+    pub ammo: Option<AmmoType>,
+}
+
+/// The inventory index and stats of the ranged weapon the entity has equipped, if any.
+pub fn get_ranged_weapon(entity: &Entity) -> Option<(usize, RangedWeapon)> {
+    entity.inventory.iter().enumerate()
+        .filter(|(_, item)| item.equipment.map_or(false, |e| e.equipped))
+        .find_map(|(id, item)| item.equipment.and_then(|e| e.ranged).map(|ranged| (id, ranged)))
+}
+
+/// The inventory index of whatever the weapon will fire next.
+pub fn find_ammo(entity: &Entity, weapon_id: usize, weapon: RangedWeapon) -> Option<usize> {
+    match weapon.ammo {
+        Some(ammo) => entity.inventory.iter().position(|item| item.item == Some(ammo.item())),
+        None => Some(weapon_id),
+    }
+}
+
+/// Fires the shooter's equipped ranged weapon at the target tile. The projectile flies along a line until it reaches
+/// the target, hits a wall or reaches a creature, and then drops to the floor unless it broke on a hit.
+/// Returns false if the shooter had nothing to fire.
+pub fn fire_projectile(shooter_id: usize, target: (i32, i32), game: &mut GameEngine) -> bool {
+    let (weapon_id, weapon) = match get_ranged_weapon(&game.entities[shooter_id]) {
+        Some(weapon) => weapon,
+        None => return false,
+    };
+    let ammo_id = match find_ammo(&game.entities[shooter_id], weapon_id, weapon) {
+        Some(ammo_id) => ammo_id,
+        None => return false,
+    };
+    let mut projectile = take_from_stack(&mut game.entities[shooter_id].inventory, ammo_id, 1);
+    if let Some(equipment) = projectile.equipment.as_mut() {
+        // a thrown weapon leaves the hand
+        equipment.equipped = false;
+    }
+
+    let origin = game.entities[shooter_id].pos();
+    let mut landing = origin;
+    let mut broke = false;
+    for (x, y) in line(origin, target).into_iter().take(weapon.range as usize) {
+        if !in_map_bounds(x, y) || game.map[x as usize][y as usize].blocked {
+            break;
+        }
+        landing = (x, y);
+        let target_id = game.entities.iter().position(|e| e.blocks && e.fighter.is_some() && e.pos() == (x, y));
+        if let Some(target_id) = target_id.filter(|&id| id != shooter_id) {
+            let (shooter, target) = mut_two(shooter_id, target_id, &mut game.entities);
+            let outcome = shooter.shoot(target, weapon.damage, &mut game.event_bus);
+            if shooter_id == PLAYER || target_id == PLAYER {
+                let msg = match outcome {
+                    AttackOutcome::Miss => format!("The {} misses the {}.", projectile.name, target.name),
+                    AttackOutcome::Critical => format!("The {} strikes the {} squarely!", projectile.name, target.name),
+                    _ => format!("The {} hits the {}.", projectile.name, target.name),
+                };
+                game.messages.add(msg, WHITE);
+            }
+            // hit or miss, the first creature in the way stops the projectile, and it drops at their feet
+            broke = outcome != AttackOutcome::Miss && rand::thread_rng().gen_range(0, 100) < AMMO_BREAK_CHANCE;
+            break;
+        }
+    }
+
+    announce_projectile(shooter_id, origin, landing, game);
+    if broke {
+        if game.is_visible(landing.0, landing.1) {
+            game.messages.add(format!("The {} breaks.", projectile.name), LIGHT_GREY);
+        }
+    } else {
+        projectile.set_pos(landing.0, landing.1);
+        projectile.always_visible = true;
+        game.entities.push(projectile);
+    }
+    true
+}
+
+/// How far the item can be thrown; heavy things don't get far.
+pub fn throw_range(item: &Entity) -> i32 {
+    let weight = item.item.as_ref().map_or(0, |i| i.weight());
+    (THROW_RANGE - weight / 2).max(MIN_THROW_RANGE)
+}
+
+/// How hard the item hits when it's thrown. A weapon made for throwing does its ranged damage, any other weapon
+/// hits about as hard as it would in the hand, and anything else hurts according to its weight.
+pub fn throw_damage(item: &Entity) -> DamageRange {
+    match item.equipment {
+        Some(Equipment { ranged: Some(RangedWeapon { ammo: None, damage, .. }), .. }) => damage,
+        Some(equipment) if equipment.power_bonus > 0 || equipment.damage.max > 0 => {
+            equipment.damage + DamageRange::new(equipment.power_bonus, equipment.power_bonus)
+        }
+        _ => {
+            let weight = item.item.as_ref().map_or(0, |i| i.weight());
+            DamageRange::new(weight / 2, weight)
+        }
+    }
+}
+
+/// Throws one of the item at the target tile. It flies along a line until it reaches the target or the end of its range,
+/// hits a wall or reaches a creature. A potion shatters wherever it stops and splashes everything around it;
+/// anything else drops to the floor.
+pub fn throw_item(thrower_id: usize, inventory_id: usize, target: (i32, i32), game: &mut GameEngine) {
+    let mut projectile = take_from_stack(&mut game.entities[thrower_id].inventory, inventory_id, 1);
+    if let Some(equipment) = projectile.equipment.as_mut() {
+        equipment.equipped = false;
+    }
+    let name = game.identification.name_of(&projectile);
+    let damage = throw_damage(&projectile);
+
+    let origin = game.entities[thrower_id].pos();
+    let mut landing = origin;
+    for (x, y) in line(origin, target).into_iter().take(throw_range(&projectile) as usize) {
+        if !in_map_bounds(x, y) || game.map[x as usize][y as usize].blocked {
+            break;
+        }
+        landing = (x, y);
+        let target_id = game.entities.iter().position(|e| e.blocks && e.fighter.is_some() && e.pos() == (x, y));
+        if let Some(target_id) = target_id.filter(|&id| id != thrower_id) {
+            if damage.max <= 0 {
+                // too light to do any harm, so it just drops at the creature's feet
+                break;
+            }
+            let (thrower, target) = mut_two(thrower_id, target_id, &mut game.entities);
+            let outcome = thrower.shoot(target, damage, &mut game.event_bus);
+            if thrower_id == PLAYER || target_id == PLAYER {
+                let msg = match outcome {
+                    AttackOutcome::Miss => format!("The {} misses the {}.", name, target.name),
+                    AttackOutcome::Critical => format!("The {} strikes the {} squarely!", name, target.name),
+                    _ => format!("The {} hits the {}.", name, target.name),
+                };
+                game.messages.add(msg, WHITE);
+            }
+            // hit or miss, the first creature in the way stops it, and it drops at their feet
+            break;
+        }
+    }
+
+    announce_projectile(thrower_id, origin, landing, game);
+    if projectile.item.as_ref().map_or(false, is_potion) {
+        shatter_potion(&projectile, landing, game);
+    } else {
+        projectile.set_pos(landing.0, landing.1);
+        projectile.always_visible = true;
+        game.entities.push(projectile);
+    }
+}
+
+// the potion breaks, and everything caught in the splash gets a dose of it, friend or foe
+fn shatter_potion(potion: &Entity, (x, y): (i32, i32), game: &mut GameEngine) {
+    let visible = game.is_visible(x, y);
+    if visible {
+        game.messages.add(format!("The {} shatters!", game.identification.name_of(potion)), LIGHT_GREY);
+    }
+    let splashed: Vec<usize> = (0..game.entities.len())
+        .filter(|&id| game.entities[id].alive && game.entities[id].fighter.is_some())
+        .filter(|&id| game.entities[id].distance(x, y) <= POTION_SPLASH_RADIUS as f32)
+        .collect();
+    if let Some(Item::Heal) = potion.item {
+        for &id in splashed.iter() {
+            game.entities[id].heal(HEAL_AMOUNT);
+            if id == PLAYER {
+                game.messages.add("You look healthier.", LIGHT_VIOLET);
+            } else if visible {
+                game.messages.add(format!("The {} looks healthier.", game.entities[id].name), LIGHT_VIOLET);
+            }
+        }
+    }
+    // seeing what the splash did gives the potion away
+    if visible && !splashed.is_empty() && game.identification.identify(potion) {
+        game.messages.add(format!("That was a {}.", potion.name), WHITE);
+    }
+}
+
+fn announce_projectile(shooter_id: usize, origin: (i32, i32), landing: (i32, i32), game: &mut GameEngine) {
+    game.add_event(GameEvent::from_type_with_data(
+        EventType::ProjectileFired,
+        HashMap::from([
+            ("shooter_name".to_string(), EventData::String(game.entities[shooter_id].name.clone())),
+            ("origin".to_string(), EventData::TupleI32I32(origin)),
+            ("landing".to_string(), EventData::TupleI32I32(landing)),
+        ])
+    ));
+}
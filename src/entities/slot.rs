@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Slot {
+    LeftHand,
+    RightHand,
+    // two-handed weapons take up both hands at once
+    BothHands,
+    Head,
+    Body,
+    Back,
+    Hands,
+    Feet,
+    LeftFinger,
+    RightFinger,
+    Neck,
+}
+
+// every place something can be worn, in the order the equipment screen lists them
+pub const EQUIPMENT_SLOTS: [Slot; 10] = [
+    Slot::RightHand,
+    Slot::LeftHand,
+    Slot::Head,
+    Slot::Body,
+    Slot::Back,
+    Slot::Hands,
+    Slot::Feet,
+    Slot::LeftFinger,
+    Slot::RightFinger,
+    Slot::Neck,
+];
+
+impl Slot {
+    /// Whether something worn in this slot gets in the way of something worn in the other.
+    pub fn overlaps(self, other: Slot) -> bool {
+        use Slot::*;
+        self == other || match (self, other) {
+            (BothHands, LeftHand) | (BothHands, RightHand) | (LeftHand, BothHands) | (RightHand, BothHands) => true,
+            _ => false,
+        }
+    }
+
+    // the other slot an item can go in when this one is taken, like a ring on the other hand
+    pub fn alternative(self) -> Option<Slot> {
+        match self {
+            Slot::LeftFinger => Some(Slot::RightFinger),
+            Slot::RightFinger => Some(Slot::LeftFinger),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Slot {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Slot::LeftHand => write!(f, "left hand"),
+            Slot::RightHand => write!(f, "right hand"),
+            Slot::BothHands => write!(f, "both hands"),
+            Slot::Head => write!(f, "head"),
+            Slot::Body => write!(f, "body"),
+            Slot::Back => write!(f, "back"),
+            Slot::Hands => write!(f, "hands"),
+            Slot::Feet => write!(f, "feet"),
+            Slot::LeftFinger => write!(f, "left finger"),
+            Slot::RightFinger => write!(f, "right finger"),
+            Slot::Neck => write!(f, "neck"),
+        }
+    }
+}
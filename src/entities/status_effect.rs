@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use tcod::Color;
+use tcod::colors::{DARK_GREEN, LIGHT_BLUE, LIGHT_GREEN, LIGHT_GREY, LIGHT_VIOLET, LIGHTEST_YELLOW, ORANGE, SEPIA, YELLOW};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StatusEffectKind {
+    Confused,
+    Poisoned,
+    Burning,
+    Stunned,
+    Hasted,
+    Slowed,
+    Regenerating,
+    Weakened,
+    Frightened,
+}
+
+/// What happens when an effect is applied to an entity that already has it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stacking {
+    Refresh,        // keep the longer duration and the stronger magnitude
+    Intensify,      // magnitudes add up, duration refreshes
+    Extend,         // durations add up
+}
+
+impl StatusEffectKind {
+    pub fn stacking(self) -> Stacking {
+        use StatusEffectKind::*;
+        match self {
+            Poisoned => Stacking::Intensify,
+            Confused | Stunned | Burning | Weakened | Frightened => Stacking::Refresh,
+            Hasted | Slowed | Regenerating => Stacking::Extend,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        use StatusEffectKind::*;
+        match self {
+            Confused => LIGHT_VIOLET,
+            Poisoned => DARK_GREEN,
+            Burning => ORANGE,
+            Stunned => YELLOW,
+            Hasted => LIGHT_BLUE,
+            Slowed => LIGHT_GREY,
+            Regenerating => LIGHT_GREEN,
+            Weakened => SEPIA,
+            Frightened => LIGHTEST_YELLOW,
+        }
+    }
+}
+
+impl std::fmt::Display for StatusEffectKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use StatusEffectKind::*;
+        match *self {
+            Confused => write!(f, "confused"),
+            Poisoned => write!(f, "poisoned"),
+            Burning => write!(f, "burning"),
+            Stunned => write!(f, "stunned"),
+            Hasted => write!(f, "hasted"),
+            Slowed => write!(f, "slowed"),
+            Regenerating => write!(f, "regenerating"),
+            Weakened => write!(f, "weakened"),
+            Frightened => write!(f, "frightened"),
+        }
+    }
+}
+
+/// A temporary effect on an entity. `magnitude` is the damage, healing or stat penalty per turn where that applies.
+/// `source` is the id of whoever inflicted it, so a poison or burn kill is credited to them.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    pub turns_remaining: i32,
+    pub magnitude: i32,
+    pub source: Option<usize>,
+}
+
+impl StatusEffect {
+    pub fn new(kind: StatusEffectKind, turns: i32, magnitude: i32) -> Self {
+        StatusEffect {
+            kind,
+            turns_remaining: turns,
+            magnitude,
+            source: None,
+        }
+    }
+
+    pub fn with_source(mut self, source_id: usize) -> Self {
+        self.source = Some(source_id);
+        self
+    }
+
+    pub fn stack_with(&mut self, other: StatusEffect) {
+        // the latest to pile on gets the credit
+        if other.source.is_some() {
+            self.source = other.source;
+        }
+        match self.kind.stacking() {
+            Stacking::Refresh => {
+                self.turns_remaining = self.turns_remaining.max(other.turns_remaining);
+                self.magnitude = self.magnitude.max(other.magnitude);
+            }
+            Stacking::Intensify => {
+                self.turns_remaining = self.turns_remaining.max(other.turns_remaining);
+                self.magnitude += other.magnitude;
+            }
+            Stacking::Extend => {
+                self.turns_remaining += other.turns_remaining;
+                self.magnitude = self.magnitude.max(other.magnitude);
+            }
+        }
+    }
+}
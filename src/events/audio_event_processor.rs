@@ -4,20 +4,24 @@ use serde::{Deserialize, Serialize};
 
 use crate::{Entity, GameEvent, Map};
 use crate::audio::audio_engine::AudioEngine;
-use crate::events::game_event_processing::{EventBusReader, EventProcessor, EventType};
+use crate::events::game_event_processing::{EventBusReader, EventData, EventProcessor, EventType};
 
 #[derive(Serialize, Deserialize)]
 pub struct AudioEventProcessor {
     event_bus_reader: EventBusReader,
     #[serde(skip)]
-    pub audio_engine: Option<AudioEngine>
+    pub audio_engine: Option<AudioEngine>,
+    // the EntityAttacked event comes before the attack sound, so remember whether it connected
+    #[serde(skip)]
+    last_attack_missed: bool
 }
 
 impl AudioEventProcessor {
     pub fn new() -> Self {
         AudioEventProcessor {
             event_bus_reader: EventBusReader{head: 0},
-            audio_engine: None
+            audio_engine: None,
+            last_attack_missed: false
         }
     }
 
@@ -31,7 +35,21 @@ impl EventProcessor for AudioEventProcessor {
     fn process(&mut self, _map: &mut Map, _entities: &mut Vec<Entity>, event_bus: &Vec<GameEvent>, max_events: usize, bus_tail: usize) {
         use EventType::*;
         if self.event_bus_reader.head != bus_tail {
-            let sample_name = match event_bus[self.event_bus_reader.head].event_type {
+            let event = &event_bus[self.event_bus_reader.head];
+            let sample_name = match event.event_type {
+                EntityAttacked => {
+                    let outcome = match event.data.get("outcome") {
+                        Some(EventData::String(outcome)) => outcome.as_str(),
+                        _ => "hit"
+                    };
+                    self.last_attack_missed = outcome == "miss";
+                    match outcome {
+                        "critical" => Some("punch".to_string()),
+                        _ => None
+                    }
+                }
+                PlayerAttack if self.last_attack_missed => None,
+                MonsterAttack if self.last_attack_missed => None,
                 PlayerAttack => Some("punch".to_string()),
                 MonsterAttack => Some("monster1".to_string()),
                 MonsterDie => Some("monster_die1".to_string()),
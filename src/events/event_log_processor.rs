@@ -31,12 +31,19 @@ impl EventProcessor for EventLogProcessor {
                     let target_pos = data_map.get(&"target_pos".to_string()).unwrap();
                     let attacker_pos = data_map.get(&"attacker_pos".to_string()).unwrap();
                     let damage = data_map.get(&"damage".to_string()).unwrap();
-                    log::info!("entity with name {} at {} attacked entity with name {} at {} for {} damage",
+                    let outcome = data_map.get(&"outcome".to_string()).unwrap();
+                    log::info!("entity with name {} at {} attacked entity with name {} at {} ({}) for {} damage",
                         attacker_name, attacker_pos,
                         target_name, target_pos,
-                        damage
+                        outcome, damage
                     );
                 }
+                MonsterCastSpell => {
+                    let data_map = event.get_data_as_flat_hashmap();
+                    let caster_name = data_map.get(&"caster_name".to_string()).unwrap();
+                    let spell = data_map.get(&"spell".to_string()).unwrap();
+                    log::info!("entity with name {} cast {}", caster_name, spell);
+                }
                 _ => {}
             }
             self.event_bus_reader.head = (self.event_bus_reader.head + 1) % max_events;
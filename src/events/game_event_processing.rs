@@ -12,9 +12,12 @@ pub enum EventType {
     PlayerDie,
     MonsterAttack,
     MonsterMove,
+    MonsterCastSpell,
     MonsterDie,
     BossDie,
-    PlayerPickupItem
+    PlayerPickupItem,
+    StatusEffectExpired,
+    ProjectileFired
 }
 
 #[derive(Serialize, Deserialize)]
@@ -21,6 +21,22 @@ impl GameOccurrenceEventProcessor {
     }
 }
 
+// whatever the dead were carrying ends up on the floor where they fell
+fn drop_carried_items(x: i32, y: i32, entities: &mut Vec<Entity>) {
+    let dropped: Vec<Entity> = entities.iter_mut()
+        .filter(|e| !e.alive && e.pos() == (x, y))
+        .flat_map(|e| e.inventory.drain(..))
+        .collect();
+    for mut item in dropped {
+        if let Some(equipment) = item.equipment.as_mut() {
+            equipment.equipped = false;
+        }
+        item.set_pos(x, y);
+        item.always_visible = true;
+        entities.push(item);
+    }
+}
+
 #[typetag::serde]
 impl EventProcessor for GameOccurrenceEventProcessor {
     fn process(&mut self, _map: &mut Map, entities: &mut Vec<Entity>, event_bus: &Vec<GameEvent>, max_events: usize, bus_tail: usize) {
@@ -28,6 +44,11 @@ impl EventProcessor for GameOccurrenceEventProcessor {
         if self.event_bus_reader.head != bus_tail {
             let event: &GameEvent = &event_bus[self.event_bus_reader.head];
             match event.event_type {
+                MonsterDie => {
+                    if let Some(EventData::TupleI32I32((x, y))) = event.data.get("position") {
+                        drop_carried_items(*x, *y, entities);
+                    }
+                }
                 BossDie => {
                     let event_data = event.data.get("position");
                     match event_data {
@@ -36,6 +57,7 @@ impl EventProcessor for GameOccurrenceEventProcessor {
                                 let mut stairs = Entity::new(*x, *y - 1, '<', WHITE, "stairs", false);
                                 stairs.always_visible = true;
                                 entities.push(stairs);
+                                drop_carried_items(*x, *y, entities);
                             },
                             _ => log::warn!("WARNING: attempted to pull position data, but it wasn't of the correct type")
                         }
@@ -0,0 +1,55 @@
+use std::any::Any;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{EventData, EventProcessor, GameEvent};
+use crate::entities::entity::Entity;
+use crate::events::game_event_processing::EventBusReader;
+use crate::map::mapgen::Map;
+use crate::map::noise::propagate_noise;
+
+/// Lets monsters hear whatever made a noise: any event carrying a `noise` volume and a `position` is spread
+/// through the map, and every monster in earshot reacts to it.
+#[derive(Serialize, Deserialize)]
+pub struct NoiseEventProcessor {
+    event_bus_reader: EventBusReader
+}
+
+impl NoiseEventProcessor {
+    pub fn new() -> Self {
+        NoiseEventProcessor {
+            event_bus_reader: EventBusReader{head: 0}
+        }
+    }
+}
+
+#[typetag::serde]
+impl EventProcessor for NoiseEventProcessor {
+    fn process(&mut self, map: &mut Map, entities: &mut Vec<Entity>, event_bus: &Vec<GameEvent>, max_events: usize, bus_tail: usize) {
+        if self.event_bus_reader.head != bus_tail {
+            let event: &GameEvent = &event_bus[self.event_bus_reader.head];
+            if let (Some(EventData::I32(noise)), Some(EventData::TupleI32I32(position))) = (event.data.get("noise"), event.data.get("position")) {
+                let heard = propagate_noise(map, *position, *noise);
+                for entity in entities.iter_mut().filter(|e| e.alive && e.ai.is_some()) {
+                    let pos = entity.pos();
+                    if let (Some(awareness), Some(volume)) = (entity.awareness.as_mut(), heard.get(&pos)) {
+                        awareness.hear_noise(*position, *volume);
+                    }
+                }
+            }
+            self.event_bus_reader.head = (self.event_bus_reader.head + 1) % max_events;
+        }
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_id(&self) -> &str {
+        "noise_event_processor"
+    }
+}
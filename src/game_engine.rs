@@ -1,22 +1,34 @@
 use std::borrow::BorrowMut;
 
 use serde::{Deserialize, Serialize};
-use tcod::{BackgroundFlag, Console, TextAlignment};
-use tcod::colors::{BLACK, DARKER_RED, LIGHT_GREEN, LIGHT_GREY, WHITE};
+use tcod::{BackgroundFlag, Color, Console, TextAlignment};
+use tcod::colors::{BLACK, DARKER_RED, LIGHT_CYAN, LIGHT_GREEN, LIGHT_GREY, WHITE, YELLOW};
 use tcod::console::{blit, Root};
-use tcod::map::FovAlgorithm;
 
 use crate::{AudioEventProcessor, Camera, Entity, EventBus, EventProcessor, GameConfig, GameEvent, in_map_bounds, MAP_HEIGHT, MAP_WIDTH, Messages, SCREEN_WIDTH, Tcod};
 use crate::save_game;
 use crate::audio::audio_engine::AudioEngine;
 use crate::graphics::render_functions::{BAR_WIDTH, display_menu, get_names_under_mouse, inventory_menu, INVENTORY_WIDTH, menu, MSG_HEIGHT, MSG_WIDTH, MSG_X, msgbox, PANEL_HEIGHT, PANEL_Y, render_bar, render_inventory_menu, render_level_up_menu};
+use crate::map::fov::FovMap;
+use crate::map::hazard::{Hazard, tick_hazards};
+use crate::map::lighting::{is_visible_to_player, LightMap, tick_transient_lights, TransientLight};
 use crate::map::mapgen::Map;
+use crate::entities::companion::share_companion_xp;
+use crate::entities::entity::ACTION_COST;
+use crate::items::corpse::tick_corpses;
+use crate::items::identification::Identification;
+use crate::entities::entity_actions::tick_status_effects;
+use crate::entities::hunger::{HungerStatus, tick_hunger};
+use crate::entities::status_effect::StatusEffectKind;
 use crate::util::ai::ai_take_turn;
 
 //fov settings
-pub const FOV_ALGO: FovAlgorithm = FovAlgorithm::Basic;
 pub const FOV_LIGHT_WALLS: bool = true;
-pub const TORCH_RADIUS: i32 = 10;
+
+//lighting settings
+pub const TORCH_RADIUS: i32 = 8;
+pub const TORCH_COLOR: Color = Color { r: 255, g: 200, b: 130 };
+pub const TORCH_INTENSITY: f32 = 1.2;
 
 pub const PLAYER: usize = 0;
 
@@ -25,6 +37,7 @@ pub const LEVEL_UP_BASE: i32 = 200;
 pub const LEVEL_UP_FACTOR: i32 = 150;
 pub const LEVEL_SCREEN_WIDTH: i32 = 40;
 pub const STATS_SCREEN_WIDTH: i32 = 30;
+pub const CONFIRM_SCREEN_WIDTH: i32 = 30;
 
 #[derive(Serialize, Deserialize)]
 pub struct GameEngine {
@@ -35,6 +48,17 @@ pub struct GameEngine {
     pub event_processors: Vec<Box<dyn EventProcessor>>,
     pub entities: Vec<Entity>,
     pub camera: Camera,
+    pub transient_lights: Vec<TransientLight>,
+    pub hazards: Vec<Hazard>,
+    pub identification: Identification,
+    // names of the uniques that have turned up this game, so none of them turns up twice
+    pub generated_uniques: Vec<String>,
+    #[serde(skip)]
+    pub fov: FovMap,
+    #[serde(skip)]
+    pub light_map: LightMap,
+    #[serde(skip)]
+    pub show_ai_debug: bool,
     #[serde(skip)]
     pub game_state: Box<GameState>
 }
@@ -70,31 +94,41 @@ impl GameEngine {
             });
     }
 
+    pub fn is_visible(&self, x: i32, y: i32) -> bool {
+        is_visible_to_player(&self.fov, &self.light_map, self.entities[PLAYER].pos(), x, y)
+    }
+
     pub fn render_all(&mut self, tcod: &mut Tcod, fov_recompute: bool) {
+        if fov_recompute {
+            // how far the player can see is limited by light, not by distance
+            let player = &self.entities[PLAYER];
+            self.fov.compute_fov(&self.map, player.x, player.y, 0, FOV_LIGHT_WALLS)
+        }
+        self.light_map.compute(&self.map, &self.entities, &self.transient_lights);
+        let fov = &self.fov;
+        let light_map = &self.light_map;
         let map: &mut Map = self.map.borrow_mut();
         let messages = self.messages.borrow_mut();
         let dungeon_level = self.dungeon_level;
         let camera = self.camera.borrow_mut();
         let player = & self.entities[PLAYER];
         camera.update(player);
+        let player_pos = player.pos();
 
-        if fov_recompute {
-            let player = &self.entities[PLAYER];
-            tcod.fov.compute_fov(player.x, player.y, TORCH_RADIUS, FOV_LIGHT_WALLS, FOV_ALGO)
-        }
         let entities: &mut Vec<Entity> = self.entities.borrow_mut();
         for y in 0..MAP_HEIGHT {
             for x in 0..MAP_WIDTH {
                 let (x_in_camera, y_in_camera) = camera.get_pos_in_camera(x, y);
                 if camera.in_bounds(x_in_camera, y_in_camera) && in_map_bounds(x, y) {
-                    let visible = tcod.fov.is_in_fov(x, y);
+                    let visible = is_visible_to_player(fov, light_map, player_pos, x, y);
+                    let tile = &map[x as usize][y as usize];
                     let color = match visible {
-                        false => map[x as usize][y as usize].dark_color,
-                        true => map[x as usize][y as usize].lit_color,
+                        false => tile.dark_color,
+                        true => light_map.shade(x, y, tile.lit_color, tile.dark_color),
                     };
                     let surface_color = match visible {
-                        false => map[x as usize][y as usize].surface_dark_color,
-                        true => map[x as usize][y as usize].surface_lit_color,
+                        false => tile.surface_dark_color,
+                        true => light_map.shade(x, y, tile.surface_lit_color, tile.surface_dark_color),
                     };
                     let explored = &mut map[x as usize][y as usize].explored;
                     if visible {
@@ -109,10 +143,16 @@ impl GameEngine {
                 }
             }
         }
+        for hazard in self.hazards.iter().filter(|h| is_visible_to_player(fov, light_map, player_pos, h.x, h.y)) {
+            let (x_in_camera, y_in_camera) = camera.get_pos_in_camera(hazard.x, hazard.y);
+            if camera.in_bounds(x_in_camera, y_in_camera) {
+                tcod.con.set_char_background(x_in_camera, y_in_camera, hazard.color(), BackgroundFlag::Set);
+            }
+        }
         let mut to_draw: Vec<_> = entities
             .iter()
             .filter(|o|
-                        tcod.fov.is_in_fov(o.x, o.y)                                            // is in fov
+                        is_visible_to_player(fov, light_map, player_pos, o.x, o.y)              // is in fov and lit
                             || (o.always_visible && map[o.x as usize][o.y as usize].explored)  // is always visible and has been explored
             )
             .collect();
@@ -120,6 +160,18 @@ impl GameEngine {
         for object in to_draw {
             object.draw(&mut tcod.con, camera);
         }
+        if self.show_ai_debug {
+            // mark every monster's state above it, whether the player can see it or not
+            for object in entities.iter().filter(|o| o.alive) {
+                if let Some(awareness) = object.awareness {
+                    let (x_in_camera, y_in_camera) = camera.get_pos_in_camera(object.x, object.y - 1);
+                    if camera.in_bounds(x_in_camera, y_in_camera) {
+                        tcod.con.set_default_foreground(awareness.state.color());
+                        tcod.con.put_char(x_in_camera, y_in_camera, awareness.state.symbol(), BackgroundFlag::None);
+                    }
+                }
+            }
+        }
         // reset GUI panel
         tcod.root.set_default_foreground(WHITE);
         tcod.panel.set_default_background(BLACK);
@@ -128,9 +180,14 @@ impl GameEngine {
         let hp = entities[PLAYER].fighter.map_or(0, |f| f.hp);
         let max_hp = entities[PLAYER].max_hp();
         render_bar(&mut tcod.panel, 1, 1, BAR_WIDTH, "HP", hp, max_hp, LIGHT_GREEN, DARKER_RED);
+        // only worth a mention once the player is going hungry
+        if let Some(status) = entities[PLAYER].hunger.map(|h| h.status()).filter(|&s| s != HungerStatus::Fed) {
+            tcod.panel.set_default_foreground(status.color());
+            tcod.panel.print_ex(1, 2, BackgroundFlag::None, TextAlignment::Left, status.to_string());
+        }
         // get names at mouse location
         tcod.panel.set_default_foreground(LIGHT_GREY);
-        tcod.panel.print_ex(1, 0, BackgroundFlag::None, TextAlignment::Left, get_names_under_mouse(tcod.mouse, entities, &tcod.fov));
+        tcod.panel.print_ex(1, 0, BackgroundFlag::None, TextAlignment::Left, get_names_under_mouse(tcod.mouse, entities, &self.identification, fov, light_map));
         // display message log
         let mut y = MSG_HEIGHT as i32;
         for &(ref msg, color) in messages.iter().rev() {     // iterate through the messages in reverse order
@@ -142,8 +199,16 @@ impl GameEngine {
             tcod.panel.set_default_foreground(color);
             tcod.panel.print_rect(MSG_X, y, MSG_WIDTH, 0, msg);
         }
-        // display game level
-        tcod.panel.print_ex(1, 3, BackgroundFlag::None, TextAlignment::Left, format!("Level {}", dungeon_level));
+        // display game level and the player's gold
+        tcod.panel.print_ex(1, 3, BackgroundFlag::None, TextAlignment::Left, format!("Level {}  Gold {}", dungeon_level, entities[PLAYER].gold));
+        // display the player's status effects
+        for (i, effect) in entities[PLAYER].status_effects.iter().take(PANEL_HEIGHT as usize - 4).enumerate() {
+            tcod.panel.set_default_foreground(effect.kind.color());
+            tcod.panel.print_ex(
+                1, 4 + i as i32, BackgroundFlag::None, TextAlignment::Left,
+                format!("{} ({})", effect.kind, effect.turns_remaining)
+            );
+        }
         blit(
             &tcod.panel,
             (0,0),
@@ -198,13 +263,42 @@ impl GameEngine {
             self.process_events();
 
             if self.entities[PLAYER].alive && player_action != DidntTakeTurn {
-                for id in 0..self.entities.len() {
-                    if self.entities[id].ai.is_some() {
-                        ai_take_turn(id, &tcod, self)
-                    }
+                self.entities[PLAYER].energy -= ACTION_COST;
+                tick_status_effects(PLAYER, self);
+                tick_hunger(self);
+                // the world carries on until the player has saved up the energy for their next action
+                while self.entities[PLAYER].alive && self.entities[PLAYER].energy < ACTION_COST {
+                    self.play_round();
+                }
+                share_companion_xp(self);
+                tick_corpses(self);
+            }
+        }
+    }
+
+    // every living entity gains energy by its speed, and monsters spend theirs on as many turns as they can afford.
+    // a stunned monster loses its turns, but its effects still wear off turn by turn
+    fn play_round(&mut self) {
+        for id in 0..self.entities.len() {
+            if !self.entities[id].alive {
+                continue;
+            }
+            self.entities[id].energy += self.entities[id].speed();
+            if id == PLAYER {
+                continue;
+            }
+            while self.entities[id].alive && self.entities[id].energy >= ACTION_COST {
+                self.entities[id].energy -= ACTION_COST;
+                if self.entities[id].ai.is_some() && !self.entities[id].has_status_effect(StatusEffectKind::Stunned) {
+                    ai_take_turn(id, self);
+                }
+                if !self.entities[id].status_effects.is_empty() {
+                    tick_status_effects(id, self);
                 }
             }
         }
+        tick_transient_lights(&mut self.transient_lights);
+        tick_hazards(self);
     }
 }
 
@@ -246,8 +340,8 @@ impl GameState {
                 render_inventory_menu(
                     tcod,
                     game,
-                    "Select an item to use by pressing the matching key, or any other to \
-                    cancel\n",
+                    "Select an item to use by pressing the matching key (shift to use several), \
+                    or any other to cancel\n",
                 );
             }
         }
@@ -261,8 +355,8 @@ impl GameState {
                 render_inventory_menu(
                     tcod,
                     game,
-                    "Select an item to drop by pressing the matching key, or any other to \
-                    cancel\n",
+                    "Select an item to drop by pressing the matching key (shift to drop several), \
+                    or any other to cancel\n",
                 );
             }
         }
@@ -287,7 +381,7 @@ impl GameState {
 use std::borrow::Borrow;
 use tcod::input::Key;
 use crate::game_engine::PlayerAction::{DidntTakeTurn, TookTurn};
-use crate::inventory::inventory_actions::{drop_item, use_item};
+use crate::inventory::inventory_actions::{choose_quantity, drop_item, use_item};
 
 impl Default for GameState {
     fn default() -> Self {
@@ -300,11 +394,19 @@ pub fn handle_keys(tcod: &mut Tcod, game: &mut GameEngine) -> PlayerAction {
 
     use tcod::input::Key;
     use crate::map::map_functions::next_level;
-    use crate::inventory::inventory_actions::{drop_item, use_item};
-    use crate::entities::entity_actions::{pick_item_up, player_move_or_attack};
+    use crate::inventory::inventory_actions::{call_item, choose_quantity, drop_item, throw_from_inventory, use_item};
+    use crate::inventory::equipment_screen::equipment_screen;
+    use crate::entities::entity_actions::{pick_item_up, player_move_or_attack, target_tile};
+    use crate::entities::ranged::{find_ammo, fire_projectile, get_ranged_weapon};
+    use crate::entities::companion::{CompanionOrder, order_companions};
     use PlayerAction::*;
 
     let player_alive = game.entities[PLAYER].alive;
+    if player_alive && tcod.key.pressed && is_action_key(tcod.key, tcod.key.text())
+        && game.entities[PLAYER].has_status_effect(StatusEffectKind::Stunned) {
+        game.messages.add("You are stunned and cannot act!", YELLOW);
+        return TookTurn;
+    }
     match (tcod.key, tcod.key.text(), player_alive) {
         (Key {code: Enter, alt: true, ..}, _, _,) => {               // the 2 dots signify that we dont care about the other values of Key. Without them, the code wouldnt compile until all values were supplied
             let fullscreen = tcod.root.is_fullscreen();
@@ -312,47 +414,48 @@ pub fn handle_keys(tcod: &mut Tcod, game: &mut GameEngine) -> PlayerAction {
             DidntTakeTurn
         },
         (Key { code: Escape, ..}, _, _, )=> return Exit,
+        (Key { code: F3, .. }, _, _) => {
+            game.show_ai_debug = !game.show_ai_debug;
+            DidntTakeTurn
+        },
 
         // movement keys
         (Key { code: Up, .. }, _, true ) | (Key { code: NumPad8, .. }, _, true ) => {
-            player_move_or_attack(0, -1, game);
-            TookTurn
+            if player_move_or_attack(0, -1, tcod, game) { TookTurn } else { DidntTakeTurn }
         },
         (Key { code: Down, .. }, _, true ) | (Key { code: NumPad2, .. }, _, true ) => {
-            player_move_or_attack(0, 1, game);
-            TookTurn
+            if player_move_or_attack(0, 1, tcod, game) { TookTurn } else { DidntTakeTurn }
         },
         (Key { code: Left, .. }, _, true ) | (Key { code: NumPad4, .. }, _, true ) => {
-            player_move_or_attack(-1, 0, game);
-            TookTurn
+            if player_move_or_attack(-1, 0, tcod, game) { TookTurn } else { DidntTakeTurn }
         },
         (Key { code: Right, .. }, _, true ) | (Key { code: NumPad6, .. }, _, true ) => {
-            player_move_or_attack(1, 0, game);
-            TookTurn
+            if player_move_or_attack(1, 0, tcod, game) { TookTurn } else { DidntTakeTurn }
         },
         (Key { code: Home, .. }, _, true ) | (Key { code: NumPad7, .. }, _, true ) => {
-            player_move_or_attack(-1, -1, game);
-            TookTurn
+            if player_move_or_attack(-1, -1, tcod, game) { TookTurn } else { DidntTakeTurn }
         },
         (Key { code: PageUp, .. }, _, true ) | (Key { code: NumPad9, .. }, _, true ) => {
-            player_move_or_attack(1, -1, game);
-            TookTurn
+            if player_move_or_attack(1, -1, tcod, game) { TookTurn } else { DidntTakeTurn }
         },
         (Key { code: End, .. }, _, true ) | (Key { code: NumPad1, .. }, _, true ) => {
-            player_move_or_attack(-1, 1, game);
-            TookTurn
+            if player_move_or_attack(-1, 1, tcod, game) { TookTurn } else { DidntTakeTurn }
         },
         (Key { code: PageDown, .. }, _, true ) | (Key { code: NumPad3, .. }, _, true ) => {
-            player_move_or_attack(1, 1, game);
-            TookTurn
+            if player_move_or_attack(1, 1, tcod, game) { TookTurn } else { DidntTakeTurn }
         },
         (Key { code: NumPad5, .. }, _, true ) | (Key { code: Text, .. }, ".", true ) => {
             TookTurn
         },
-        (Key { code: Text, .. }, "g", true ) => {
+        (Key { code: Text, .. }, "g", true ) | (Key { code: Text, .. }, "G", true ) => {
             let item_id = game.entities.iter().position(|object| object.pos() == game.entities[PLAYER].pos() && object.item.is_some());
             if let Some(item_id) = item_id {
-                pick_item_up(item_id, game);
+                // a capital G asks how much of a pile to take
+                let quantity = game.entities[item_id].quantity;
+                let count = if tcod.key.text() == "G" && quantity > 1 { choose_quantity(quantity, tcod) } else { Some(quantity) };
+                if let Some(count) = count {
+                    pick_item_up(item_id, count, game);
+                }
             }
             DidntTakeTurn
         },
@@ -366,23 +469,83 @@ pub fn handle_keys(tcod: &mut Tcod, game: &mut GameEngine) -> PlayerAction {
             game.game_state = Box::new(GameState::drop_from_inventory());
             DidntTakeTurn
         },
+        (Key {code: Text, ..}, "f", true) => {
+            match get_ranged_weapon(&game.entities[PLAYER]) {
+                None => {
+                    game.messages.add("You have no ranged weapon equipped.", WHITE);
+                    DidntTakeTurn
+                }
+                Some((weapon_id, weapon)) => {
+                    if find_ammo(&game.entities[PLAYER], weapon_id, weapon).is_none() {
+                        game.messages.add("You have nothing to fire.", WHITE);
+                        return DidntTakeTurn;
+                    }
+                    game.messages.add("Left-click a target tile to fire; right-click or Esc to cancel", LIGHT_CYAN);
+                    match target_tile(tcod, game, Some(weapon.range as f32)) {
+                        Some(target) => {
+                            fire_projectile(PLAYER, target, game);
+                            TookTurn
+                        }
+                        None => DidntTakeTurn
+                    }
+                }
+            }
+        },
+        (Key {code: Text, ..}, "t", true) => {
+            if throw_from_inventory(tcod, game) { TookTurn } else { DidntTakeTurn }
+        },
         (Key {code: Text, ..}, "<", true) => {
             let player_on_stairs = game.entities
             .iter()
             .any(|object| object.pos() == game.entities[PLAYER].pos() && object.name == "stairs");
             if player_on_stairs {
-                next_level(tcod, game);
+                next_level(game);
             }
             DidntTakeTurn
         },
+        (Key {code: Text, ..}, "o", true) => {
+            let orders = [CompanionOrder::Follow, CompanionOrder::Stay, CompanionOrder::Attack];
+            if !game.entities.iter().any(|e| e.alive && e.companion.is_some()) {
+                game.messages.add("You have no companions to give orders to.", WHITE);
+                return DidntTakeTurn;
+            }
+            let options: Vec<String> = orders.iter().map(|order| order.to_string()).collect();
+            match menu("Order your companions to:", &options, CONFIRM_SCREEN_WIDTH, &mut tcod.root) {
+                Some(choice) => {
+                    order_companions(orders[choice], &mut game.entities);
+                    game.messages.add(format!("\"{}!\"", orders[choice]), LIGHT_CYAN);
+                    TookTurn
+                }
+                None => DidntTakeTurn
+            }
+        },
+        (Key {code: Text, ..}, "e", true) => {
+            if equipment_screen(tcod, game) { TookTurn } else { DidntTakeTurn }
+        },
+        (Key {code: Text, ..}, "n", true) => {
+            call_item(tcod, game);
+            DidntTakeTurn
+        },
+        (Key {code: Text, ..}, "k", true) => {
+            let discoveries = game.identification.discoveries();
+            let msg = if discoveries.is_empty() {
+                "You haven't identified anything yet.".to_string()
+            } else {
+                format!("Known items:\n\n{}", discoveries.join("\n"))
+            };
+            msgbox(&msg, INVENTORY_WIDTH, &mut tcod.root);
+            DidntTakeTurn
+        },
         (Key {code: Text, ..}, "c", true) => {
             let player = &game.entities[PLAYER];
             let level = player.level;
             let level_up_xp = LEVEL_UP_BASE + level * LEVEL_UP_FACTOR;
             if let Some(fighter) = player.fighter.as_ref() {
+                let resistances = player.resistances();
                 let msg = format!(
-                    "Player stats: \n Level: {}\nExperience: {}\nExperience to level up: {}\n\nMaximum HP: {}\nAttack: {}\nbase_Defense: {}",
-                    level, fighter.xp, level_up_xp, player.max_hp(), player.power(), player.defense()
+                    "Player stats: \n Level: {}\nExperience: {}\nExperience to level up: {}\n\nMaximum HP: {}\nAttack: {}\nbase_Defense: {}\nStealth: {}\n\nResistances:\nPhysical: {}%\nFire: {}%\nLightning: {}%\nCold: {}%\nNecrotic: {}%",
+                    level, fighter.xp, level_up_xp, player.max_hp(), player.power(), player.defense(), player.stealth(),
+                    resistances.physical, resistances.fire, resistances.lightning, resistances.cold, resistances.necrotic
                 );
                 msgbox(&msg, STATS_SCREEN_WIDTH, &mut tcod.root);
 
@@ -393,6 +556,17 @@ pub fn handle_keys(tcod: &mut Tcod, game: &mut GameEngine) -> PlayerAction {
     }
 }
 
+// keys that spend the player's turn on doing something, as opposed to the ones that just bring up a screen
+fn is_action_key(key: Key, text: &str) -> bool {
+    use tcod::input::KeyCode::*;
+    match key.code {
+        Up | Down | Left | Right | Home | End | PageUp | PageDown => true,
+        NumPad1 | NumPad2 | NumPad3 | NumPad4 | NumPad5 | NumPad6 | NumPad7 | NumPad8 | NumPad9 => true,
+        Text => text == "." || text == "f" || text == "t",
+        _ => false,
+    }
+}
+
 fn handle_inventory_input(
     tcod: &mut Tcod,
     game: &mut GameEngine,
@@ -460,7 +634,7 @@ fn handle_inventory(
     key: Key,
     tcod: &mut Tcod,
     game: &mut GameEngine,
-    inventory_action: &'static dyn Fn(usize, &mut Tcod, &mut GameEngine)
+    inventory_action: &'static dyn Fn(usize, i32, &mut Tcod, &mut GameEngine)
 ) -> PlayerAction {
     let inventory = &game.entities[PLAYER].inventory;
     let options = if inventory.len() == 0 {
@@ -479,7 +653,21 @@ fn handle_inventory(
     if key.printable.is_alphabetic() {
         let index = key.printable.to_ascii_lowercase() as usize - 'a' as usize;
         if index < options.len() {
-            inventory_action(index, tcod, game);
+            // a capital letter asks how many of a stack to use or drop
+            let quantity = game.entities[PLAYER].inventory.get(index).map_or(1, |item| item.quantity);
+            let count = if key.printable.is_uppercase() && quantity > 1 {
+                match choose_quantity(quantity, tcod) {
+                    Some(count) => count,
+                    None => return DidntTakeTurn,
+                }
+            } else {
+                1
+            };
+            if game.entities[PLAYER].has_status_effect(StatusEffectKind::Stunned) {
+                game.messages.add("You are stunned and cannot act!", YELLOW);
+            } else {
+                inventory_action(index, count, tcod, game);
+            }
             log::info!("Changing game state to main");
             game.game_state = Box::new(GameState::main());
             TookTurn
@@ -3,8 +3,11 @@ use tcod::colors::{DARKER_SEPIA, WHITE, YELLOW};
 use tcod::console::{blit, Offscreen, Root};
 use tcod::input::Mouse;
 
-use crate::{Entity, FovMap, Map, MAP_HEIGHT, MAP_WIDTH, SCREEN_HEIGHT, SCREEN_WIDTH, Tcod};
+use crate::{Entity, SCREEN_HEIGHT, SCREEN_WIDTH, Tcod};
 use crate::game_engine::{GameEngine, LEVEL_SCREEN_WIDTH, LEVEL_UP_BASE, LEVEL_UP_FACTOR, PLAYER};
+use crate::items::identification::Identification;
+use crate::map::fov::FovMap;
+use crate::map::lighting::{is_visible_to_player, LightMap};
 
 pub const INVENTORY_WIDTH: i32 = 50;
 
@@ -17,29 +20,16 @@ pub const MSG_X: i32 = BAR_WIDTH + 2;
 pub const MSG_WIDTH: i32 = SCREEN_WIDTH - BAR_WIDTH - 2;
 pub const MSG_HEIGHT: usize = PANEL_HEIGHT as usize - 1;
 
-pub fn initialize_fov(tcod: &mut Tcod, map: &Map) {
-    for y in 0..MAP_HEIGHT as usize {
-        for x in 0..MAP_WIDTH as usize {
-            tcod.fov.set(
-                x as i32,
-                y as i32,
-                !map[x][y].block_sight,
-                !map[x][y].blocked,
-            );
-        }
-    }
-}
-
-pub fn inventory_menu(inventory: &[Entity], header: &str, root: &mut Root) -> Option<usize> {
+pub fn inventory_menu(inventory: &[Entity], identification: &Identification, header: &str, root: &mut Root) -> Option<usize> {
     let options = if inventory.len() == 0 {
         vec!["Inventory is empty.".into()]
     } else {
         inventory.iter().map(|item| {
             match item.equipment {
                 Some(equipment) if equipment.equipped => {
-                    format!("{} (on {})", item.name, equipment.slot)
+                    format!("{} (on {})", identification.describe(item), equipment.slot)
                 }
-                _ => item.name.clone()
+                _ => identification.describe(item)
             }
 
         }).collect()
@@ -59,12 +49,45 @@ pub fn msgbox(text: &str, width: i32, root: &mut Root) {
     menu(text, options, width, root);
 }
 
-pub fn get_names_under_mouse(mouse: Mouse, objects: &[Entity], fov_map: &FovMap) -> String {
+/// Lets the player type a line of text under the header. Enter accepts it and Esc gives up.
+pub fn text_input(header: &str, width: i32, root: &mut Root) -> Option<String> {
+    use tcod::input::KeyCode::{Backspace, Enter, Escape};
+
+    let header_height = root.get_height_rect(0, 0, width, SCREEN_HEIGHT, header);
+    let height = header_height + 1;
+    let mut text = String::new();
+    loop {
+        let mut window = Offscreen::new(width, height);
+        window.set_default_foreground(WHITE);
+        window.print_rect_ex(0, 0, width, height, BackgroundFlag::None, TextAlignment::Left, header);
+        window.print_ex(0, header_height, BackgroundFlag::None, TextAlignment::Left, format!("> {}_", text));
+
+        let x = SCREEN_WIDTH / 2 - width / 2;
+        let y = SCREEN_HEIGHT / 2 - height / 2;
+        blit(&window, (0,0), (width, height), root, (x, y), 1.0, 0.7);
+        root.flush();
+
+        let key = root.wait_for_keypress(true);
+        match key.code {
+            Enter => return Some(text.trim().to_string()),
+            Escape => return None,
+            Backspace => {
+                text.pop();
+            }
+            // leave room for the prompt and the cursor
+            _ if !key.printable.is_control() && (text.len() as i32) < width - 3 => text.push(key.printable),
+            _ => {}
+        }
+    }
+}
+
+pub fn get_names_under_mouse(mouse: Mouse, objects: &[Entity], identification: &Identification, fov_map: &FovMap, light_map: &LightMap) -> String {
     let (x, y) = (mouse.cx as i32, mouse.cy as i32);
+    let player_pos = objects[PLAYER].pos();
     let names = objects
         .iter()
-        .filter(|obj| obj.pos() == (x, y) && fov_map.is_in_fov(obj.x, obj.y))
-        .map(|obj| obj.name.clone())
+        .filter(|obj| obj.pos() == (x, y) && is_visible_to_player(fov_map, light_map, player_pos, obj.x, obj.y))
+        .map(|obj| if obj.companion.is_some() { format!("{} (ally)", obj.name) } else { identification.name_of(obj) })
         .collect::<Vec<_>>();
 
     names.join(", ")
@@ -73,15 +96,16 @@ pub fn get_names_under_mouse(mouse: Mouse, objects: &[Entity], fov_map: &FovMap)
 
 pub fn render_inventory_menu(tcod: &mut Tcod, game: &mut GameEngine, header: &str) {
     let inventory = &game.entities[PLAYER].inventory;
+    let identification = &game.identification;
     let options = if inventory.len() == 0 {
         vec!["Inventory is empty.".into()]
     } else {
         inventory.iter().map(|item| {
             match item.equipment {
                 Some(equipment) if equipment.equipped => {
-                    format!("{} (on {})", item.name, equipment.slot)
+                    format!("{} (on {})", identification.describe(item), equipment.slot)
                 }
-                _ => item.name.clone()
+                _ => identification.describe(item)
             }
         }).collect()
     };
@@ -0,0 +1,97 @@
+use tcod::colors::WHITE;
+
+use crate::Messages;
+use crate::entities::entity::Entity;
+use crate::entities::slot::{Slot, EQUIPMENT_SLOTS};
+use crate::framework::Tcod;
+use crate::game_engine::{GameEngine, PLAYER};
+use crate::graphics::render_functions::menu;
+use crate::inventory::inventory_actions::{equip_item_in_slot, get_equipped_id_in_slot};
+use crate::items::affix::Rarity;
+
+pub const EQUIPMENT_SCREEN_WIDTH: i32 = 60;
+
+/// Shows what the player has on in every slot. Picking a slot lists the gear in the pack that could go there,
+/// along with how each piece would change the player's stats, and picking one of those puts it on.
+/// Returns whether the player changed anything, which takes a turn.
+pub fn equipment_screen(tcod: &mut Tcod, game: &mut GameEngine) -> bool {
+    let player = &game.entities[PLAYER];
+    let options: Vec<String> = EQUIPMENT_SLOTS.iter().map(|&slot| {
+        let worn = get_equipped_id_in_slot(slot, &player.inventory)
+            .map_or("-".to_string(), |id| gear_name(&player.inventory[id]));
+        format!("{:<14}{}", format!("{}:", slot), worn)
+    }).collect();
+    let slot = match menu("Equipment - choose a slot to change what's in it\n", &options, EQUIPMENT_SCREEN_WIDTH, &mut tcod.root) {
+        Some(choice) => EQUIPMENT_SLOTS[choice],
+        None => return false,
+    };
+
+    let candidates: Vec<usize> = (0..player.inventory.len())
+        .filter(|&id| player.inventory[id].equipment.map_or(false, |e| {
+            !e.equipped && (e.slot.overlaps(slot) || e.slot.alternative() == Some(slot))
+        }))
+        .collect();
+    if candidates.is_empty() {
+        game.messages.add(format!("You have nothing else to wear on your {}.", slot), WHITE);
+        return false;
+    }
+    let options: Vec<String> = candidates.iter()
+        .map(|&id| format!("{} ({})", gear_name(&player.inventory[id]), compare(player, id, slot)))
+        .collect();
+    let header = format!("Choose something to wear on your {}\n", slot);
+    match menu(&header, &options, EQUIPMENT_SCREEN_WIDTH, &mut tcod.root) {
+        Some(choice) => {
+            let inventory = &mut game.entities[PLAYER].inventory;
+            let target = target_slot(&inventory[candidates[choice]], slot);
+            equip_item_in_slot(inventory, candidates[choice], target, &mut game.messages);
+            true
+        }
+        None => false,
+    }
+}
+
+// a ring goes on the finger the player picked; anything else, like a two-handed weapon picked for one hand,
+// goes in its own slot
+fn target_slot(item: &Entity, chosen: Slot) -> Slot {
+    match item.equipment {
+        Some(equipment) if equipment.slot.alternative() == Some(chosen) => chosen,
+        Some(equipment) => equipment.slot,
+        None => chosen,
+    }
+}
+
+// the item's name, marked with its rarity if it's anything better than common
+fn gear_name(item: &Entity) -> String {
+    match item.equipment.map(|e| e.rarity) {
+        Some(rarity) if rarity != Rarity::Common => format!("{} [{}]", item.name, rarity),
+        _ => item.name.clone(),
+    }
+}
+
+// how putting the item on in the chosen slot would change the wearer's stats, like "power +2, defense -1"
+fn compare(wearer: &Entity, inventory_id: usize, slot: Slot) -> String {
+    let mut preview = wearer.clone();
+    let target = target_slot(&wearer.inventory[inventory_id], slot);
+    equip_item_in_slot(&mut preview.inventory, inventory_id, target, &mut Messages::new());
+    let (before, after) = (wearer.resistances(), preview.resistances());
+    let stats = [
+        ("power", wearer.power(), preview.power()),
+        ("defense", wearer.defense(), preview.defense()),
+        ("max hp", wearer.max_hp(), preview.max_hp()),
+        ("max damage", wearer.damage_range().max, preview.damage_range().max),
+        ("physical res", before.physical, after.physical),
+        ("fire res", before.fire, after.fire),
+        ("lightning res", before.lightning, after.lightning),
+        ("cold res", before.cold, after.cold),
+        ("necrotic res", before.necrotic, after.necrotic),
+    ];
+    let changes: Vec<String> = stats.iter()
+        .filter(|&&(_, before, after)| before != after)
+        .map(|&(name, before, after)| format!("{} {:+}", name, after - before))
+        .collect();
+    if changes.is_empty() {
+        "no change".to_string()
+    } else {
+        changes.join(", ")
+    }
+}
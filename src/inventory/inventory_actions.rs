@@ -1,15 +1,38 @@
 use std::borrow::BorrowMut;
-use tcod::colors::{DARK_RED, GOLD, LIGHT_BLUE, LIGHT_CYAN, LIGHT_GREEN, ORANGE, RED, WHITE, YELLOW};
+use rand::Rng;
+use tcod::colors::{DARK_GREEN, DARK_RED, GOLD, LIGHT_BLUE, LIGHT_CYAN, LIGHT_GREEN, LIGHT_GREY, LIGHTEST_SEPIA, ORANGE, RED, WHITE, YELLOW};
+use crate::{GAME_CONFIGS, Messages};
 use crate::framework::Tcod;
-use crate::game_engine::{GameEngine, PLAYER};
+use crate::game_engine::{CONFIRM_SCREEN_WIDTH, GameEngine, PLAYER};
+use crate::entities::companion::recruit;
+use crate::entities::damage::{Damage, DamageType};
 use crate::entities::entity::Entity;
-use crate::entities::entity_actions::target_tile;
+use crate::entities::entity_actions::{deal_damage, DIRECTIONS, target_tile};
+use crate::entities::faction::Faction;
+use crate::entities::monster::Monster;
+use crate::entities::ranged::{throw_item, throw_range};
 use crate::entities::slot::Slot;
+use crate::entities::status_effect::{StatusEffect, StatusEffectKind};
+use crate::graphics::render_functions::{INVENTORY_WIDTH, menu, text_input};
+use crate::items::corpse::CORPSE_ROTTEN_TURNS;
 use crate::items::item::*;
-use crate::map::map_functions::{closest_monster, target_monster};
-use crate::util::ai::Ai;
+use crate::items::wand::*;
+use crate::map::lighting::{LightSource, TransientLight};
+use crate::map::map_functions::{closest_monster, is_blocked, target_monster};
+use crate::map::mapgen::make_monster;
+use crate::util::death_callback::DeathCallback;
 
-pub fn use_item(inventory_id: usize, tcod: &mut Tcod, game: &mut GameEngine) {
+/// Uses the item `count` times, or until a use doesn't go through. Only a stack can be used more than once.
+pub fn use_item(inventory_id: usize, count: i32, tcod: &mut Tcod, game: &mut GameEngine) {
+    for _ in 0..count {
+        if !use_one(inventory_id, tcod, game) {
+            break;
+        }
+    }
+}
+
+// uses a single item off the stack, returning whether it was used up
+fn use_one(inventory_id: usize, tcod: &mut Tcod, game: &mut GameEngine) -> bool {
     use Item::*;
     if let Some(item) = &game.entities[PLAYER].inventory[inventory_id].item {
         let on_use = match item {
@@ -17,32 +40,141 @@ pub fn use_item(inventory_id: usize, tcod: &mut Tcod, game: &mut GameEngine) {
             Lightning => cast_lightning,
             Confuse => cast_confuse,
             Fireball => cast_fireball,
+            RaiseSkeleton => cast_raise_skeleton,
+            Tame => cast_tame,
+            Identify => cast_identify,
+            Recharge => cast_recharge,
+            Wand { .. } => zap_wand,
+            Corpse { .. } => eat_corpse,
+            Ration => eat_ration,
             Artifact{name: _, value: _} => examine_artifact,
+            Gold => count_gold,
             Sword => toggle_equipment,
             Shield => toggle_equipment,
+            Greataxe | Helmet | LeatherArmor | Cloak | Gauntlets | Boots | Ring | Amulet => toggle_equipment,
+            Bow | Crossbow | ThrowingKnife => toggle_equipment,
+            Arrow | Bolt => examine_ammo,
         };
         match on_use(inventory_id, tcod, game) {
             UseResult::UsedUp => {
-                game.entities[PLAYER].inventory.remove(inventory_id);
+                learn_by_use(inventory_id, game);
+                take_from_stack(&mut game.entities[PLAYER].inventory, inventory_id, 1);
+                true
             }
-            UseResult::UsedAndKept => {}
+            UseResult::UsedAndKept => false,
             UseResult::Cancelled => {
                 game.messages.add("Cancelled", WHITE);
+                false
             }
         }
     } else {
         game.messages.add(format!("The {} cannot be used.", game.entities[PLAYER].inventory[inventory_id].name), WHITE);
+        false
+    }
+}
+
+/// Puts the item in the inventory, on top of a stack of the same kind if there is one, and returns where it went.
+pub fn add_to_inventory(inventory: &mut Vec<Entity>, item: Entity) -> usize {
+    match inventory.iter().position(|other| other.stacks_with(&item)) {
+        Some(id) => {
+            inventory[id].quantity += item.quantity;
+            id
+        }
+        None => {
+            inventory.push(item);
+            inventory.len() - 1
+        }
+    }
+}
+
+/// Takes `count` items off the stack, and takes the stack out of the inventory once it's all gone.
+pub fn take_from_stack(inventory: &mut Vec<Entity>, inventory_id: usize, count: i32) -> Entity {
+    let stack = &mut inventory[inventory_id];
+    if count < stack.quantity {
+        stack.quantity -= count;
+        let mut taken = stack.clone();
+        taken.quantity = count;
+        taken
+    } else {
+        inventory.remove(inventory_id)
     }
 }
 
-pub fn drop_item(inventory_id: usize, _: &mut Tcod, game: &mut GameEngine) {
+/// Asks the player how many of a stack of `max` they mean. Leaving it blank means the lot.
+pub fn choose_quantity(max: i32, tcod: &mut Tcod) -> Option<i32> {
+    let answer = text_input(&format!("How many? (1-{}, blank for all)", max), CONFIRM_SCREEN_WIDTH, &mut tcod.root)?;
+    if answer.is_empty() {
+        return Some(max);
+    }
+    match answer.parse::<i32>() {
+        Ok(count) if count > 0 => Some(count.min(max)),
+        _ => None,
+    }
+}
+
+// using up a potion or scroll gives away what it was
+fn learn_by_use(inventory_id: usize, game: &mut GameEngine) {
+    let item = &game.entities[PLAYER].inventory[inventory_id];
+    if game.identification.identify(item) {
+        game.messages.add(format!("That was a {}.", item.name), WHITE);
+    }
+}
+
+/// Lets the player give a kind of potion or scroll they haven't identified a name of their own.
+pub fn call_item(tcod: &mut Tcod, game: &mut GameEngine) {
+    let inventory = &game.entities[PLAYER].inventory;
+    let unknown: Vec<usize> = (0..inventory.len())
+        .filter(|&id| inventory[id].item.as_ref().map_or(false, |item| !game.identification.is_identified(item)))
+        .collect();
+    if unknown.is_empty() {
+        game.messages.add("You have nothing unidentified to name.", WHITE);
+        return;
+    }
+    let options: Vec<String> = unknown.iter().map(|&id| game.identification.describe(&inventory[id])).collect();
+    let choice = match menu("Choose an item to name:", &options, INVENTORY_WIDTH, &mut tcod.root) {
+        Some(choice) => choice,
+        None => return,
+    };
+    let header = format!("What do you want to call the {}?", options[choice]);
+    if let Some(label) = text_input(&header, INVENTORY_WIDTH, &mut tcod.root) {
+        if let Some(item) = game.entities[PLAYER].inventory[unknown[choice]].item.as_ref() {
+            game.identification.label(item, &label);
+        }
+    }
+}
+
+/// Lets the player pick anything in the pack and a tile to throw it at. Returns whether anything was thrown,
+/// which takes a turn.
+pub fn throw_from_inventory(tcod: &mut Tcod, game: &mut GameEngine) -> bool {
+    let inventory = &game.entities[PLAYER].inventory;
+    if inventory.is_empty() {
+        game.messages.add("You have nothing to throw.", WHITE);
+        return false;
+    }
+    let options: Vec<String> = inventory.iter().map(|item| game.identification.describe(item)).collect();
+    let inventory_id = match menu("Choose an item to throw:", &options, INVENTORY_WIDTH, &mut tcod.root) {
+        Some(choice) => choice,
+        None => return false,
+    };
+    let range = throw_range(&inventory[inventory_id]);
+    game.messages.add("Left-click a target tile to throw at; right-click or Esc to cancel", LIGHT_CYAN);
+    match target_tile(tcod, game, Some(range as f32)) {
+        Some(target) => {
+            throw_item(PLAYER, inventory_id, target, game);
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn drop_item(inventory_id: usize, count: i32, _: &mut Tcod, game: &mut GameEngine) {
     //TODO dont default to players inventory
-    let mut item = game.entities[PLAYER].inventory.remove(inventory_id);
+    let mut item = take_from_stack(&mut game.entities[PLAYER].inventory, inventory_id, count);
     if item.equipment.is_some() {
         item.unequip(&mut game.messages);
     }
     item.set_pos(game.entities[PLAYER].x, game.entities[PLAYER].y);
-    game.messages.add(format!("You dropped the {}.", item.name), YELLOW);
+    game.messages.add(format!("You dropped the {}.", game.identification.describe(&item)), YELLOW);
     game.entities.push(item);
 }
 
@@ -66,23 +198,24 @@ pub fn cast_heal(
 
 pub fn cast_lightning(
     _inventory_id: usize,
-    tcod: &mut Tcod,
+    _tcod: &mut Tcod,
     game: &mut GameEngine,
 ) -> UseResult {
 
-    let monster_id = closest_monster(tcod, game, LIGHTNING_RANGE);
+    let monster_id = closest_monster(game, LIGHTNING_RANGE);
     let entities: &mut Vec<Entity> = game.entities.borrow_mut();
     let event_bus = game.event_bus.borrow_mut();
     let messages = game.messages.borrow_mut();
     if let Some(monster_id) = monster_id {
-        game.messages.add(
-            format!("A lightning bolt strikes the {}! It deals {} points of damage.", entities[monster_id].name, LIGHTNING_DAMAGE),
+        let damage = Damage::new(LIGHTNING_DAMAGE, DamageType::Lightning);
+        messages.add(
+            format!(
+                "A lightning bolt strikes the {}! It deals {} points of damage.",
+                entities[monster_id].name, entities[monster_id].damage_after_resistances(damage)
+            ),
             LIGHT_BLUE
         );
-        if let Some(xp) = entities[monster_id].take_damage(LIGHTNING_DAMAGE, event_bus) {
-            // TODO: determine attacker and award xp to them, not automatically to player
-            entities[PLAYER].fighter.as_mut().unwrap().xp += xp;
-        }
+        deal_damage(monster_id, Some(PLAYER), damage, entities, event_bus);
         UseResult::UsedUp
     } else {
         messages.add("No enemies are within range.", RED);
@@ -94,11 +227,7 @@ pub fn cast_confuse(_inventory_id: usize, tcod: &mut Tcod, game: &mut GameEngine
     // let monster_id = target_monster(CONFUSE_RANGE, objects, tcod);
     let monster_id = target_monster(tcod, game, Some(CONFUSE_RANGE as f32));
     if let Some(monster_id) = monster_id {
-        let old_ai = game.entities[monster_id].ai.take().unwrap_or(Ai::Basic);
-        game.entities[monster_id].ai = Some(Ai::Confused {
-            previous_ai: Box::new(old_ai),
-            num_turns: CONFUSE_NUM_TURNS
-        });
+        game.entities[monster_id].add_status_effect(StatusEffect::new(StatusEffectKind::Confused, CONFUSE_NUM_TURNS, 0));
         game.messages.add(format!("The eyes of the {} glaze over, and it starts to stumble around.", game.entities[monster_id].name), LIGHT_GREEN);
         UseResult::UsedUp
     } else {
@@ -113,33 +242,271 @@ pub fn cast_fireball(_inventory_id: usize, tcod: &mut Tcod, game: &mut GameEngin
         Some(tile_pos) => tile_pos,
         None => return UseResult::Cancelled,
     };
+    explode_fireball(PLAYER, x, y, game);
+    UseResult::UsedUp
+}
+
+/// Burns every fighter within the fireball's radius of the given tile, on behalf of whoever cast it.
+pub fn explode_fireball(source_id: usize, x: i32, y: i32, game: &mut GameEngine) {
     let entities: &mut Vec<Entity> = game.entities.borrow_mut();
     let event_bus = game.event_bus.borrow_mut();
     let messages = game.messages.borrow_mut();
     messages.add(format!("The fireball explodes, burning everything within {} tiles.", FIREBALL_RADIUS), ORANGE);
-    let mut xp_to_gain = 0;
-    for (id, obj) in entities.iter_mut().enumerate() {
-        if obj.distance(x, y) <= FIREBALL_RADIUS as f32 && obj.fighter.is_some() {
-            game.messages.add(format!("The {} gets burned for {} hit points.", obj.name, FIREBALL_DAMAGE), ORANGE);
-            if let Some(xp) = obj.take_damage(FIREBALL_DAMAGE, event_bus) {
-                if id != PLAYER {
-                    xp_to_gain += xp;
-                }
+    let caster_id = entities[source_id].id;
+    let damage = Damage::new(FIREBALL_DAMAGE, DamageType::Fire);
+    for id in 0..entities.len() {
+        if entities[id].distance(x, y) <= FIREBALL_RADIUS as f32 && entities[id].fighter.is_some() {
+            messages.add(
+                format!("The {} gets burned for {} hit points.", entities[id].name, entities[id].damage_after_resistances(damage)),
+                ORANGE
+            );
+            deal_damage(id, Some(source_id), damage, entities, event_bus);
+            if entities[id].alive {
+                entities[id].add_status_effect(
+                    StatusEffect::new(StatusEffectKind::Burning, FIREBALL_BURN_TURNS, FIREBALL_BURN_DAMAGE).with_source(caster_id)
+                );
             }
         }
     }
-    // TODO: determine attacker rather than awarding to player
-    entities[PLAYER].fighter.as_mut().unwrap().xp += xp_to_gain;
+    game.transient_lights.push(TransientLight {
+        x,
+        y,
+        light: LightSource { radius: FIREBALL_RADIUS + 2, color: ORANGE, intensity: 1.5 },
+        turns_remaining: FIREBALL_FLASH_TURNS,
+    });
+}
+
+pub fn cast_raise_skeleton(_inventory_id: usize, _tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
+    // a corpse close at hand makes for a better vessel than dust
+    let corpse_id = closest_corpse(game, RAISE_RANGE);
+    if let Some(corpse_id) = corpse_id {
+        let corpse = game.entities.swap_remove(corpse_id);
+        let mut skeleton = make_monster(Monster::Skeleton, corpse.x, corpse.y);
+        recruit(&mut skeleton);
+        skeleton.alive = true;
+        game.entities.push(skeleton);
+        game.messages.add(format!("The {} twitch, then lurch upright as a skeleton to serve you.", corpse.name), LIGHTEST_SEPIA);
+        return UseResult::UsedUp;
+    }
+    let (player_x, player_y) = game.entities[PLAYER].pos();
+    let spot = DIRECTIONS.iter()
+        .map(|&(dx, dy)| (player_x + dx, player_y + dy))
+        .find(|&(x, y)| !is_blocked(x, y, &game.map, &game.entities));
+    match spot {
+        Some((x, y)) => {
+            let mut skeleton = make_monster(Monster::Skeleton, x, y);
+            recruit(&mut skeleton);
+            skeleton.alive = true;
+            game.entities.push(skeleton);
+            game.messages.add("Bones knit together out of the dust, and a skeleton rises to serve you.", LIGHTEST_SEPIA);
+            UseResult::UsedUp
+        }
+        None => {
+            game.messages.add("There's no room for anything to rise beside you.", RED);
+            UseResult::Cancelled
+        }
+    }
+}
+
+// the nearest corpse the player can see, with nothing standing on it
+fn closest_corpse(game: &GameEngine, max_range: i32) -> Option<usize> {
+    let player = &game.entities[PLAYER];
+    game.entities.iter().enumerate()
+        .filter(|(_, e)| match e.item { Some(Item::Corpse { .. }) => true, _ => false })
+        .filter(|(_, e)| player.distance_to(e) <= max_range as f32 && game.is_visible(e.x, e.y))
+        .filter(|(_, e)| !is_blocked(e.x, e.y, &game.map, &game.entities))
+        .min_by(|(_, a), (_, b)| player.distance_to(a).partial_cmp(&player.distance_to(b)).unwrap())
+        .map(|(id, _)| id)
+}
+
+pub fn eat_corpse(inventory_id: usize, _tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
+    let player = &mut game.entities[PLAYER];
+    let corpse = &player.inventory[inventory_id];
+    let name = corpse.name.clone();
+    let (nutrition, rotten) = match &corpse.item {
+        Some(Item::Corpse { nutrition, turns_left }) => (*nutrition, *turns_left <= CORPSE_ROTTEN_TURNS),
+        _ => return UseResult::Cancelled,
+    };
+    if nutrition <= 0 {
+        game.messages.add(format!("There's nothing on the {} worth eating.", name), WHITE);
+        return UseResult::Cancelled;
+    }
+    if !feed(player, nutrition) {
+        game.messages.add("You're too full to eat another bite.", WHITE);
+        return UseResult::Cancelled;
+    }
+    if rotten {
+        player.add_status_effect(StatusEffect::new(StatusEffectKind::Poisoned, ROTTEN_POISON_TURNS, ROTTEN_POISON_DAMAGE));
+        game.messages.add(format!("You choke down the {}. Your stomach heaves.", name), DARK_GREEN);
+    } else {
+        player.add_status_effect(StatusEffect::new(StatusEffectKind::Regenerating, MEAL_REGEN_TURNS, MEAL_REGEN_AMOUNT));
+        game.messages.add(format!("You eat the {}. You feel your strength returning.", name), LIGHT_GREEN);
+    }
+    UseResult::UsedUp
+}
+
+pub fn eat_ration(_inventory_id: usize, _tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
+    if !feed(&mut game.entities[PLAYER], GAME_CONFIGS.hunger.ration_nutrition) {
+        game.messages.add("You're too full to eat another bite.", WHITE);
+        return UseResult::Cancelled;
+    }
+    game.messages.add("You wolf down the ration. That hit the spot.", LIGHT_GREEN);
+    UseResult::UsedUp
+}
+
+// fills the eater up, unless they're already too full to eat; returns whether they ate
+fn feed(eater: &mut Entity, nutrition: i32) -> bool {
+    match eater.hunger.as_mut() {
+        Some(hunger) if hunger.is_full() => false,
+        Some(hunger) => {
+            hunger.eat(nutrition);
+            true
+        }
+        // anything that doesn't get hungry can eat whenever it likes
+        None => true,
+    }
+}
+
+pub fn cast_tame(_inventory_id: usize, tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
+    game.messages.add("Left-click a creature to tame it; right-click or Esc to cancel", LIGHT_CYAN);
+    let monster_id = match target_monster(tcod, game, Some(TAME_RANGE as f32)) {
+        Some(monster_id) => monster_id,
+        None => return UseResult::Cancelled,
+    };
+    let monster = &mut game.entities[monster_id];
+    let fighter = match monster.fighter {
+        Some(fighter) if monster.ai.is_some() && monster.companion.is_none() => fighter,
+        _ => {
+            game.messages.add(format!("The {} can't be tamed.", monster.name), RED);
+            return UseResult::Cancelled;
+        }
+    };
+    // wild animals come around easily, but anything else has to be beaten into it first
+    let beaten = fighter.hp * 100 / monster.max_hp().max(1) <= TAME_HP_PERCENT;
+    let willing = fighter.on_death != DeathCallback::Boss && (monster.faction == Some(Faction::Wildlife) || beaten);
+    if willing {
+        recruit(monster);
+        game.messages.add(format!("The {} bows its head and falls in beside you.", monster.name), LIGHT_GREEN);
+    } else {
+        game.messages.add(format!("The {} shrugs off the spell.", monster.name), RED);
+    }
     UseResult::UsedUp
 }
 
+pub fn cast_identify(inventory_id: usize, tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
+    let inventory = &game.entities[PLAYER].inventory;
+    let unknown: Vec<usize> = (0..inventory.len())
+        .filter(|&id| id != inventory_id)
+        .filter(|&id| inventory[id].item.as_ref().map_or(false, |item| !game.identification.is_identified(item)))
+        .collect();
+    let chosen = match unknown.len() {
+        0 => {
+            game.messages.add("You have nothing that needs identifying.", WHITE);
+            return UseResult::Cancelled;
+        }
+        1 => unknown[0],
+        _ => {
+            let options: Vec<String> = unknown.iter().map(|&id| game.identification.describe(&inventory[id])).collect();
+            match menu("Choose an item to identify:", &options, INVENTORY_WIDTH, &mut tcod.root) {
+                Some(choice) => unknown[choice],
+                None => return UseResult::Cancelled,
+            }
+        }
+    };
+    let item = &game.entities[PLAYER].inventory[chosen];
+    let appearance = game.identification.name_of(item);
+    game.identification.identify(item);
+    game.messages.add(format!("The {} is a {}.", appearance, item.name), LIGHT_CYAN);
+    UseResult::UsedUp
+}
+
+/// Casts the wand's spell just as the scroll would, at the cost of a charge. The wand crumbles away once
+/// its last charge is spent; until then it stays in the pack.
+pub fn zap_wand(inventory_id: usize, tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
+    let effect = match game.entities[PLAYER].inventory[inventory_id].item {
+        Some(Item::Wand { effect, .. }) => effect,
+        _ => return UseResult::Cancelled,
+    };
+    let result = match effect {
+        WandEffect::Lightning => cast_lightning(inventory_id, tcod, game),
+        WandEffect::Fireball => cast_fireball(inventory_id, tcod, game),
+        WandEffect::Confusion => cast_confuse(inventory_id, tcod, game),
+    };
+    if let UseResult::Cancelled = result {
+        return UseResult::Cancelled;
+    }
+    learn_by_use(inventory_id, game);
+    let charges = game.entities[PLAYER].inventory[inventory_id].item.as_mut().map_or(0, spend_charge);
+    if charges > 0 {
+        UseResult::UsedAndKept
+    } else {
+        game.messages.add("Its last charge spent, the wand crumbles to dust.", LIGHT_GREY);
+        UseResult::UsedUp
+    }
+}
+
+/// Puts more charges into a wand. The more it's already holding, the likelier it is to blow up in the
+/// player's face instead.
+pub fn cast_recharge(inventory_id: usize, tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
+    let inventory = &game.entities[PLAYER].inventory;
+    let wands: Vec<usize> = (0..inventory.len())
+        .filter(|&id| match inventory[id].item { Some(Item::Wand { .. }) => true, _ => false })
+        .collect();
+    let wand_id = match wands.len() {
+        0 => {
+            game.messages.add("You have no wand to recharge.", WHITE);
+            return UseResult::Cancelled;
+        }
+        1 => wands[0],
+        _ => {
+            let options: Vec<String> = wands.iter().map(|&id| game.identification.describe(&inventory[id])).collect();
+            match menu("Choose a wand to recharge:", &options, INVENTORY_WIDTH, &mut tcod.root) {
+                Some(choice) => wands[choice],
+                None => return UseResult::Cancelled,
+            }
+        }
+    };
+    let (effect, charges) = match inventory[wand_id].item {
+        Some(Item::Wand { effect, charges }) => (effect, charges),
+        _ => return UseResult::Cancelled,
+    };
+    let name = game.identification.name_of(&inventory[wand_id]);
+    if rand::thread_rng().gen_range(0, 100) >= recharge_explode_chance(charges) {
+        let added = rand::thread_rng().gen_range(RECHARGE_MIN, RECHARGE_MAX + 1);
+        if let Some(wand) = game.entities[PLAYER].inventory[wand_id].item.as_mut() {
+            add_charges(wand, added);
+        }
+        game.messages.add(format!("The {} hums with fresh power.", name), LIGHT_CYAN);
+        return UseResult::UsedUp;
+    }
+
+    // the scroll is spent either way, but losing the wand as well shifts the pack around, so both are
+    // taken out here, the later one first
+    learn_by_use(inventory_id, game);
+    let inventory = &mut game.entities[PLAYER].inventory;
+    if wand_id > inventory_id {
+        inventory.remove(wand_id);
+        take_from_stack(inventory, inventory_id, 1);
+    } else {
+        take_from_stack(inventory, inventory_id, 1);
+        inventory.remove(wand_id);
+    }
+    let damage = explosion_damage(effect, charges);
+    game.messages.add(
+        format!("The {} overloads and explodes! You take {} damage.", name, game.entities[PLAYER].damage_after_resistances(damage)),
+        ORANGE
+    );
+    deal_damage(PLAYER, None, damage, &mut game.entities, &mut game.event_bus);
+    UseResult::UsedAndKept
+}
+
 pub fn examine_artifact(inventory_id: usize, _tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
     //TODO: dont default to player inventory
     match &game.entities[PLAYER].inventory[inventory_id].item {
         Some(item) => {
             match item {
                 Item::Artifact {name, value} => {
-                    game.messages.add(format!("This artifact is named {} and has a value of {}", name, value), GOLD);
+                    game.messages.add(format!("This artifact is named {} and has a value of {} gold. A shopkeeper would pay it in full.", name, value), GOLD);
                     return UseResult::UsedAndKept
                 },
                 _ => {
@@ -155,6 +522,20 @@ pub fn examine_artifact(inventory_id: usize, _tcod: &mut Tcod, game: &mut GameEn
     };
 }
 
+// gold never stays in the pack, but anything that ends up there is added to the purse
+pub fn count_gold(inventory_id: usize, _tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
+    let gold = game.entities[PLAYER].inventory.remove(inventory_id);
+    game.entities[PLAYER].gold += gold.quantity;
+    game.messages.add(format!("You count {} gold into your purse.", gold.quantity), GOLD);
+    UseResult::UsedAndKept
+}
+
+pub fn examine_ammo(inventory_id: usize, _tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
+    let name = game.entities[PLAYER].inventory[inventory_id].name.clone();
+    game.messages.add(format!("Equip something that fires the {} and press 'f' to shoot.", name), WHITE);
+    UseResult::UsedAndKept
+}
+
 pub fn toggle_equipment(inventory_id: usize, _tcod: &mut Tcod, game: &mut GameEngine) -> UseResult {
     //TODO: dont default to player inventory
     let messages = game.messages.borrow_mut();
@@ -163,20 +544,45 @@ pub fn toggle_equipment(inventory_id: usize, _tcod: &mut Tcod, game: &mut GameEn
         Some(equipment) => equipment,
         None => return UseResult::Cancelled,
     };
-    if let Some(current_equipment_id) = get_equipped_id_in_slot(equipment.slot, &player.inventory) {
-        player.inventory[current_equipment_id].unequip(messages);
-    }
     if equipment.equipped {
         player.inventory[inventory_id].unequip(messages);
     } else {
-        player.inventory[inventory_id].equip(messages);
+        equip_item(&mut player.inventory, inventory_id, messages);
     }
     UseResult::UsedAndKept
 }
 
+/// Puts the item on, taking off whatever is in its way first. A ring goes on whichever finger is free.
+pub fn equip_item(inventory: &mut [Entity], inventory_id: usize, messages: &mut Messages) {
+    let mut slot = match inventory[inventory_id].equipment {
+        Some(equipment) => equipment.slot,
+        None => return,
+    };
+    if let Some(other) = slot.alternative() {
+        if get_equipped_id_in_slot(slot, inventory).is_some() && get_equipped_id_in_slot(other, inventory).is_none() {
+            slot = other;
+        }
+    }
+    equip_item_in_slot(inventory, inventory_id, slot, messages);
+}
+
+/// Puts the item on in the given slot, which has to be its own or the alternative to it, taking off
+/// whatever is in the way first.
+pub fn equip_item_in_slot(inventory: &mut [Entity], inventory_id: usize, slot: Slot, messages: &mut Messages) {
+    // a two-handed weapon can have something in each hand to clear out
+    while let Some(current_equipment_id) = get_equipped_id_in_slot(slot, inventory) {
+        inventory[current_equipment_id].unequip(messages);
+    }
+    if let Some(equipment) = inventory[inventory_id].equipment.as_mut() {
+        equipment.slot = slot;
+    }
+    inventory[inventory_id].equip(messages);
+}
+
+/// Whatever is equipped in the slot, or in the way of it, like a two-handed weapon when the slot is one hand.
 pub fn get_equipped_id_in_slot(slot: Slot, inventory: &[Entity]) -> Option<usize> {
     for (inventory_id, item) in inventory.iter().enumerate() {
-        if item.equipment.as_ref().map_or(false, |e| e.equipped && e.slot == slot) {
+        if item.equipment.as_ref().map_or(false, |e| e.equipped && e.slot.overlaps(slot)) {
             return Some(inventory_id)
         }
     }
@@ -0,0 +1,133 @@
+use tcod::colors::{GOLD, LIGHT_BLUE, RED, WHITE};
+
+use crate::entities::companion::recruit;
+use crate::entities::entity_actions::DIRECTIONS;
+use crate::entities::monster::Monster;
+use crate::framework::Tcod;
+use crate::game_engine::{GameEngine, PLAYER};
+use crate::graphics::render_functions::menu;
+use crate::inventory::inventory_actions::{add_to_inventory, take_from_stack};
+use crate::items::shop::{mercenary_cost, sale_price};
+use crate::map::map_functions::is_blocked;
+use crate::map::mapgen::make_monster;
+
+pub const SHOP_SCREEN_WIDTH: i32 = 50;
+
+/// Trades with the shopkeeper until the player walks away. Returns whether any gold changed hands,
+/// which takes a turn.
+pub fn shop_screen(shopkeeper_id: usize, tcod: &mut Tcod, game: &mut GameEngine) -> bool {
+    let mut traded = false;
+    loop {
+        let header = format!(
+            "\"Welcome, traveller! See anything you like?\"\nYou have {} gold.\n",
+            game.entities[PLAYER].gold
+        );
+        let options = ["Buy", "Sell", "Hire a mercenary", "Leave"];
+        match menu(&header, &options, SHOP_SCREEN_WIDTH, &mut tcod.root) {
+            Some(0) => traded |= buy(shopkeeper_id, tcod, game),
+            Some(1) => traded |= sell(shopkeeper_id, tcod, game),
+            Some(2) => traded |= hire_mercenary(tcod, game),
+            _ => return traded,
+        }
+    }
+}
+
+fn buy(shopkeeper_id: usize, tcod: &mut Tcod, game: &mut GameEngine) -> bool {
+    let shopkeeper = &game.entities[shopkeeper_id];
+    let shop = match shopkeeper.shop {
+        Some(shop) => shop,
+        None => return false,
+    };
+    if shopkeeper.inventory.is_empty() {
+        game.messages.add("The shelves are bare.", WHITE);
+        return false;
+    }
+    let options: Vec<String> = shopkeeper.inventory.iter()
+        .map(|item| format!("{} - {} gold", game.identification.describe(item), shop.asking_price(item, &game.identification)))
+        .collect();
+    let header = format!("Buy which item? You have {} gold.\n", game.entities[PLAYER].gold);
+    let id = match menu(&header, &options, SHOP_SCREEN_WIDTH, &mut tcod.root) {
+        Some(id) => id,
+        None => return false,
+    };
+    let price = shop.asking_price(&shopkeeper.inventory[id], &game.identification);
+    let name = game.identification.name_of(&shopkeeper.inventory[id]);
+    let player = &game.entities[PLAYER];
+    if player.gold < price {
+        game.messages.add(format!("You can't afford the {}.", name), RED);
+        return false;
+    }
+    let has_stack = player.inventory.iter().any(|item| item.stacks_with(&shopkeeper.inventory[id]));
+    if player.inventory.len() >= 26 && !has_stack {
+        game.messages.add(format!("Your pockets are full - you've no room for the {}.", name), RED);
+        return false;
+    }
+    let item = take_from_stack(&mut game.entities[shopkeeper_id].inventory, id, 1);
+    game.entities[PLAYER].gold -= price;
+    add_to_inventory(&mut game.entities[PLAYER].inventory, item);
+    game.messages.add(format!("You buy the {} for {} gold.", name, price), GOLD);
+    true
+}
+
+fn sell(shopkeeper_id: usize, tcod: &mut Tcod, game: &mut GameEngine) -> bool {
+    let inventory = &game.entities[PLAYER].inventory;
+    // nobody buys the clothes off your back
+    let for_sale: Vec<usize> = (0..inventory.len())
+        .filter(|&id| !inventory[id].equipment.map_or(false, |e| e.equipped) && sale_price(&inventory[id], &game.identification) > 0)
+        .collect();
+    if for_sale.is_empty() {
+        game.messages.add("You have nothing the shopkeeper wants.", WHITE);
+        return false;
+    }
+    let options: Vec<String> = for_sale.iter()
+        .map(|&id| format!("{} - {} gold", game.identification.describe(&inventory[id]), sale_price(&inventory[id], &game.identification)))
+        .collect();
+    let id = match menu("Sell which item?\n", &options, SHOP_SCREEN_WIDTH, &mut tcod.root) {
+        Some(choice) => for_sale[choice],
+        None => return false,
+    };
+    let price = sale_price(&inventory[id], &game.identification);
+    let name = game.identification.name_of(&inventory[id]);
+    let stock = &game.entities[shopkeeper_id].inventory;
+    if stock.len() >= 26 && !stock.iter().any(|item| item.stacks_with(&inventory[id])) {
+        game.messages.add(format!("The shopkeeper has no room left for the {}.", name), RED);
+        return false;
+    }
+    let item = take_from_stack(&mut game.entities[PLAYER].inventory, id, 1);
+    game.entities[PLAYER].gold += price;
+    add_to_inventory(&mut game.entities[shopkeeper_id].inventory, item);
+    game.messages.add(format!("You sell the {} for {} gold.", name, price), GOLD);
+    true
+}
+
+// a sellsword who'll follow the player for the rest of the run, if the price is right
+fn hire_mercenary(tcod: &mut Tcod, game: &mut GameEngine) -> bool {
+    let cost = mercenary_cost(game.dungeon_level);
+    let question = format!("A sellsword will fight at your side for {} gold. Hire them?", cost);
+    if menu(&question, &["Yes", "No"], SHOP_SCREEN_WIDTH, &mut tcod.root) != Some(0) {
+        return false;
+    }
+    if game.entities[PLAYER].gold < cost {
+        game.messages.add("You can't afford a sellsword.", RED);
+        return false;
+    }
+    let (player_x, player_y) = game.entities[PLAYER].pos();
+    let spot = DIRECTIONS.iter()
+        .map(|&(dx, dy)| (player_x + dx, player_y + dy))
+        .find(|&(x, y)| !is_blocked(x, y, &game.map, &game.entities));
+    match spot {
+        Some((x, y)) => {
+            let mut mercenary = make_monster(Monster::Mercenary, x, y);
+            recruit(&mut mercenary);
+            mercenary.alive = true;
+            game.entities.push(mercenary);
+            game.entities[PLAYER].gold -= cost;
+            game.messages.add("A sellsword pockets your gold and falls in beside you.", LIGHT_BLUE);
+            true
+        }
+        None => {
+            game.messages.add("There's no room for a sellsword to stand beside you.", RED);
+            false
+        }
+    }
+}
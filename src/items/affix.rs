@@ -0,0 +1,251 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::Color;
+use tcod::colors::{GOLD, LIGHT_BLUE, YELLOW};
+
+use crate::Transition;
+use crate::entities::damage::Resistances;
+use crate::entities::entity::Entity;
+use crate::entities::equipment::{Equipment, OnHit};
+use crate::entities::slot::Slot;
+use crate::entities::status_effect::StatusEffectKind;
+use crate::items::item::Item;
+use crate::map::mapgen::from_dungeon_level;
+
+// chance in percent, by depth, of a piece of gear turning up better than common
+const MAGIC_CHANCE: &[Transition] = &[Transition { level: 1, value: 15 }, Transition { level: 3, value: 25 }, Transition { level: 6, value: 35 }];
+const RARE_CHANCE: &[Transition] = &[Transition { level: 2, value: 3 }, Transition { level: 4, value: 8 }, Transition { level: 7, value: 15 }];
+const UNIQUE_CHANCE: &[Transition] = &[Transition { level: 4, value: 1 }, Transition { level: 7, value: 3 }];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Rarity {
+    Common,
+    Magic,
+    Rare,
+    Unique,
+}
+
+impl Rarity {
+    // common gear keeps the colour of its base item
+    pub fn color(self) -> Option<Color> {
+        match self {
+            Rarity::Common => None,
+            Rarity::Magic => Some(LIGHT_BLUE),
+            Rarity::Rare => Some(YELLOW),
+            Rarity::Unique => Some(GOLD),
+        }
+    }
+}
+
+impl std::fmt::Display for Rarity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Rarity::Common => write!(f, "common"),
+            Rarity::Magic => write!(f, "magic"),
+            Rarity::Rare => write!(f, "rare"),
+            Rarity::Unique => write!(f, "unique"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AffixPosition {
+    Prefix,
+    Suffix,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AffixTarget {
+    Weapon,
+    Armor,
+    Any,
+}
+
+/// What an affix or a unique adds on top of the base item. An item carries at most one on-hit effect,
+/// which `enchant` makes sure of when it picks the affixes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bonus {
+    pub power: i32,
+    pub defense: i32,
+    pub max_hp: i32,
+    pub resistances: Resistances,
+    pub on_hit: Option<OnHit>,
+}
+
+impl Bonus {
+    fn apply(&self, equipment: &mut Equipment) {
+        equipment.power_bonus += self.power;
+        equipment.defense_bonus += self.defense;
+        equipment.max_hp_bonus += self.max_hp;
+        equipment.resistances = equipment.resistances + self.resistances;
+        if self.on_hit.is_some() {
+            equipment.on_hit = self.on_hit;
+        }
+    }
+}
+
+/// A modifier rolled onto magic and rare gear, which also lends the item part of its name:
+/// a "keen sword of the bear" has rolled both a prefix and a suffix. Affixes only start turning up
+/// from `min_level` down.
+pub struct Affix {
+    pub name: &'static str,
+    pub position: AffixPosition,
+    pub target: AffixTarget,
+    pub min_level: u32,
+    pub bonus: Bonus,
+}
+
+/// A one-off piece of gear with a name of its own, built on top of one of the ordinary items.
+pub struct UniqueItem {
+    pub name: &'static str,
+    pub base: Item,
+    pub bonus: Bonus,
+}
+
+/// Rolls a rarity for a freshly made piece of gear and dresses it up to match. Deeper levels turn up
+/// better gear more often, and stronger affixes. Each unique turns up at most once a game, so the ones
+/// already made are skipped and any new one is added to `generated_uniques`. Anything that can't be
+/// equipped is left alone.
+pub fn enchant(object: &mut Entity, level: u32, generated_uniques: &mut Vec<String>) {
+    let mut equipment = match object.equipment {
+        Some(equipment) => equipment,
+        None => return,
+    };
+    let mut rarity = roll_rarity(level);
+    if rarity == Rarity::Unique {
+        let uniques: Vec<&UniqueItem> = UNIQUES.iter()
+            .filter(|u| object.item.as_ref() == Some(&u.base) && !generated_uniques.iter().any(|name| name == u.name))
+            .collect();
+        if uniques.is_empty() {
+            // there's no unique left for this kind of item, so it has to make do with being rare
+            rarity = Rarity::Rare;
+        } else {
+            let unique = uniques[rand::thread_rng().gen_range(0, uniques.len())];
+            unique.bonus.apply(&mut equipment);
+            object.name = unique.name.to_string();
+            generated_uniques.push(unique.name.to_string());
+        }
+    }
+    let positions: &[AffixPosition] = match rarity {
+        Rarity::Magic if rand::thread_rng().gen() => &[AffixPosition::Prefix],
+        Rarity::Magic => &[AffixPosition::Suffix],
+        Rarity::Rare => &[AffixPosition::Prefix, AffixPosition::Suffix],
+        _ => &[],
+    };
+    let target = if is_weapon(&equipment) { AffixTarget::Weapon } else { AffixTarget::Armor };
+    for &position in positions {
+        // gear only has room for one on-hit effect, so a rare item that already rolled one can't roll another
+        let candidates: Vec<&Affix> = AFFIXES.iter()
+            .filter(|a| a.position == position && a.min_level <= level && (a.target == target || a.target == AffixTarget::Any))
+            .filter(|a| a.bonus.on_hit.is_none() || equipment.on_hit.is_none())
+            .collect();
+        if candidates.is_empty() {
+            continue;
+        }
+        let affix = candidates[rand::thread_rng().gen_range(0, candidates.len())];
+        affix.bonus.apply(&mut equipment);
+        object.name = match position {
+            AffixPosition::Prefix => format!("{} {}", affix.name, object.name),
+            AffixPosition::Suffix => format!("{} {}", object.name, affix.name),
+        };
+    }
+    equipment.rarity = rarity;
+    object.equipment = Some(equipment);
+    if let Some(color) = rarity.color() {
+        object.color = color;
+    }
+}
+
+fn roll_rarity(level: u32) -> Rarity {
+    let roll = rand::thread_rng().gen_range(0, 100);
+    let unique = from_dungeon_level(UNIQUE_CHANCE, level);
+    let rare = unique + from_dungeon_level(RARE_CHANCE, level);
+    let magic = rare + from_dungeon_level(MAGIC_CHANCE, level);
+    match roll {
+        r if r < unique => Rarity::Unique,
+        r if r < rare => Rarity::Rare,
+        r if r < magic => Rarity::Magic,
+        _ => Rarity::Common,
+    }
+}
+
+fn is_weapon(equipment: &Equipment) -> bool {
+    match equipment.slot {
+        Slot::RightHand | Slot::BothHands => true,
+        _ => false,
+    }
+}
+
+const NO_RESISTANCES: Resistances = Resistances { physical: 0, fire: 0, lightning: 0, cold: 0, necrotic: 0 };
+const NO_BONUS: Bonus = Bonus { power: 0, defense: 0, max_hp: 0, resistances: NO_RESISTANCES, on_hit: None };
+
+pub const AFFIXES: &[Affix] = &[
+    // prefixes
+    Affix { name: "keen", position: AffixPosition::Prefix, target: AffixTarget::Weapon, min_level: 1, bonus: Bonus { power: 1, ..NO_BONUS } },
+    Affix { name: "vicious", position: AffixPosition::Prefix, target: AffixTarget::Weapon, min_level: 4, bonus: Bonus { power: 2, ..NO_BONUS } },
+    Affix { name: "brutal", position: AffixPosition::Prefix, target: AffixTarget::Weapon, min_level: 7, bonus: Bonus { power: 3, ..NO_BONUS } },
+    Affix {
+        name: "venomous", position: AffixPosition::Prefix, target: AffixTarget::Weapon, min_level: 2,
+        bonus: Bonus { on_hit: Some(OnHit { effect: StatusEffectKind::Poisoned, chance: 30, turns: 5, magnitude: 1 }), ..NO_BONUS },
+    },
+    Affix {
+        name: "flaming", position: AffixPosition::Prefix, target: AffixTarget::Weapon, min_level: 3,
+        bonus: Bonus { on_hit: Some(OnHit { effect: StatusEffectKind::Burning, chance: 25, turns: 3, magnitude: 2 }), ..NO_BONUS },
+    },
+    Affix {
+        name: "staggering", position: AffixPosition::Prefix, target: AffixTarget::Weapon, min_level: 5,
+        bonus: Bonus { on_hit: Some(OnHit { effect: StatusEffectKind::Stunned, chance: 10, turns: 1, magnitude: 0 }), ..NO_BONUS },
+    },
+    Affix { name: "sturdy", position: AffixPosition::Prefix, target: AffixTarget::Armor, min_level: 1, bonus: Bonus { defense: 1, ..NO_BONUS } },
+    Affix { name: "reinforced", position: AffixPosition::Prefix, target: AffixTarget::Armor, min_level: 4, bonus: Bonus { defense: 2, ..NO_BONUS } },
+    Affix {
+        name: "fireproof", position: AffixPosition::Prefix, target: AffixTarget::Armor, min_level: 2,
+        bonus: Bonus { resistances: Resistances { fire: 25, ..NO_RESISTANCES }, ..NO_BONUS },
+    },
+    Affix {
+        name: "grounded", position: AffixPosition::Prefix, target: AffixTarget::Armor, min_level: 2,
+        bonus: Bonus { resistances: Resistances { lightning: 25, ..NO_RESISTANCES }, ..NO_BONUS },
+    },
+    // suffixes
+    Affix { name: "of the bear", position: AffixPosition::Suffix, target: AffixTarget::Any, min_level: 1, bonus: Bonus { max_hp: 5, ..NO_BONUS } },
+    Affix { name: "of the troll", position: AffixPosition::Suffix, target: AffixTarget::Any, min_level: 5, bonus: Bonus { max_hp: 15, ..NO_BONUS } },
+    Affix {
+        name: "of warmth", position: AffixPosition::Suffix, target: AffixTarget::Armor, min_level: 2,
+        bonus: Bonus { resistances: Resistances { cold: 25, ..NO_RESISTANCES }, ..NO_BONUS },
+    },
+    Affix {
+        name: "of the grave", position: AffixPosition::Suffix, target: AffixTarget::Any, min_level: 4,
+        bonus: Bonus { resistances: Resistances { necrotic: 25, ..NO_RESISTANCES }, ..NO_BONUS },
+    },
+    Affix {
+        name: "of sapping", position: AffixPosition::Suffix, target: AffixTarget::Weapon, min_level: 3,
+        bonus: Bonus { on_hit: Some(OnHit { effect: StatusEffectKind::Weakened, chance: 25, turns: 4, magnitude: 1 }), ..NO_BONUS },
+    },
+    Affix {
+        name: "of binding", position: AffixPosition::Suffix, target: AffixTarget::Weapon, min_level: 5,
+        bonus: Bonus { on_hit: Some(OnHit { effect: StatusEffectKind::Slowed, chance: 20, turns: 3, magnitude: 0 }), ..NO_BONUS },
+    },
+];
+
+pub const UNIQUES: &[UniqueItem] = &[
+    UniqueItem {
+        name: "Grimtooth",
+        base: Item::Sword,
+        bonus: Bonus { power: 3, on_hit: Some(OnHit { effect: StatusEffectKind::Poisoned, chance: 50, turns: 6, magnitude: 2 }), ..NO_BONUS },
+    },
+    UniqueItem {
+        name: "Skullsplitter",
+        base: Item::Greataxe,
+        bonus: Bonus { power: 4, on_hit: Some(OnHit { effect: StatusEffectKind::Stunned, chance: 20, turns: 1, magnitude: 0 }), ..NO_BONUS },
+    },
+    UniqueItem {
+        name: "the Ashen Aegis",
+        base: Item::Shield,
+        bonus: Bonus { defense: 2, resistances: Resistances { fire: 50, ..NO_RESISTANCES }, ..NO_BONUS },
+    },
+    UniqueItem {
+        name: "the Pale Shroud",
+        base: Item::Cloak,
+        bonus: Bonus { defense: 1, resistances: Resistances { cold: 50, necrotic: 50, ..NO_RESISTANCES }, ..NO_BONUS },
+    },
+];
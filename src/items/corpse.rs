@@ -0,0 +1,51 @@
+use tcod::colors::LIGHT_GREY;
+
+use crate::entities::entity::Entity;
+use crate::entities::faction::Faction;
+use crate::game_engine::{GameEngine, PLAYER};
+use crate::items::item::Item;
+
+// turns a corpse lasts before it rots away completely, and how many are left when it starts to turn
+pub const CORPSE_DECAY_TURNS: i32 = 300;
+pub const CORPSE_ROTTEN_TURNS: i32 = 100;
+// how filling a corpse is for every point of the creature's max hp
+pub const CORPSE_NUTRITION_PER_HP: i32 = 10;
+
+/// The corpse a creature leaves behind. There's no meat on the undead, so their remains are no use as food.
+pub fn corpse_of(entity: &Entity) -> Item {
+    let nutrition = match entity.faction {
+        Some(Faction::Undead) => 0,
+        _ => entity.fighter.map_or(0, |f| f.base_max_hp) * CORPSE_NUTRITION_PER_HP,
+    };
+    Item::Corpse { nutrition, turns_left: CORPSE_DECAY_TURNS }
+}
+
+// ages the corpse by a turn
+fn decay(corpse: &mut Entity) {
+    if let Some(Item::Corpse { turns_left, .. }) = corpse.item.as_mut() {
+        *turns_left -= 1;
+        if *turns_left == CORPSE_ROTTEN_TURNS {
+            corpse.name = format!("rotting {}", corpse.name);
+        }
+    }
+}
+
+fn rotted_away(entity: &Entity) -> bool {
+    match entity.item {
+        Some(Item::Corpse { turns_left, .. }) => turns_left <= 0,
+        _ => false,
+    }
+}
+
+/// Rots every corpse a little more, on the floor and in the player's pack, and clears away the ones that are gone.
+pub fn tick_corpses(game: &mut GameEngine) {
+    game.entities.iter_mut().for_each(decay);
+    game.entities.retain(|e| !rotted_away(e));
+    let player = &mut game.entities[PLAYER];
+    player.inventory.iter_mut().for_each(decay);
+    let before = player.inventory.len();
+    player.inventory.retain(|e| !rotted_away(e));
+    if player.inventory.len() < before {
+        game.messages.add("Something in your pack has rotted away to nothing.", LIGHT_GREY);
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::entity::Entity;
+use crate::items::item::Item;
+use crate::items::wand::WAND_EFFECTS;
+use crate::util::namegen::generate_artifact_name;
+
+// the potions and scrolls that have to be identified, along with the wands; everything else is obvious at a glance
+const IDENTIFIABLE: &[Item] = &[
+    Item::Heal,
+    Item::Lightning,
+    Item::Confuse,
+    Item::Fireball,
+    Item::RaiseSkeleton,
+    Item::Tame,
+    Item::Identify,
+    Item::Recharge,
+];
+
+const POTION_APPEARANCES: &[&str] = &[
+    "murky", "fizzing", "cloudy", "smoky", "glowing", "bubbling", "oily", "swirling",
+];
+
+const WAND_APPEARANCES: &[&str] = &[
+    "oak", "bone", "iron", "glass", "ebony", "copper",
+];
+
+fn kind_key(item: &Item) -> Option<String> {
+    match item {
+        // every wand of a kind looks the same, however many charges it has left
+        Item::Wand { effect, .. } => Some(format!("Wand{:?}", effect)),
+        _ if IDENTIFIABLE.contains(item) => Some(format!("{:?}", item)),
+        _ => None,
+    }
+}
+
+// the item's real name, with the charges left if it's a wand
+fn known_name(entity: &Entity) -> String {
+    match entity.item {
+        Some(Item::Wand { charges, .. }) => format!("{} ({} charges)", entity.name, charges),
+        _ => entity.name.clone(),
+    }
+}
+
+// pluralises the thing being named, so "scroll of lightning bolt" becomes "scrolls of lightning bolt"
+fn plural(name: &str) -> String {
+    let head_end = [" of ", " labelled ", " called "].iter()
+        .filter_map(|separator| name.find(separator))
+        .min()
+        .unwrap_or(name.len());
+    let (head, rest) = name.split_at(head_end);
+    let suffix = if head.ends_with('s') || head.ends_with('x') || head.ends_with("ch") { "es" } else { "s" };
+    format!("{}{}{}", head, suffix, rest)
+}
+
+pub fn is_potion(item: &Item) -> bool {
+    match item {
+        Item::Heal => true,
+        _ => false,
+    }
+}
+
+/// What the player knows about the potions and scrolls in this run. Every kind gets a random appearance
+/// when the game starts and goes by it until the player identifies it, either by using one or with a
+/// scroll of identify. Until then the player can call it whatever they like.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Identification {
+    appearances: HashMap<String, String>,
+    // the real names of the kinds the player has identified
+    identified: HashMap<String, String>,
+    labels: HashMap<String, String>,
+}
+
+impl Identification {
+    pub fn new() -> Self {
+        let mut colours: Vec<&str> = POTION_APPEARANCES.to_vec();
+        rand::thread_rng().shuffle(&mut colours);
+        let mut appearances: HashMap<String, String> = HashMap::new();
+        for item in IDENTIFIABLE {
+            let appearance = if is_potion(item) {
+                format!("{} potion", colours.pop().unwrap())
+            } else {
+                // no two kinds of scroll can share a label
+                loop {
+                    let label = format!("scroll labelled {}", generate_artifact_name(2, 4).to_uppercase());
+                    if !appearances.values().any(|a| *a == label) {
+                        break label;
+                    }
+                }
+            };
+            appearances.insert(kind_key(item).unwrap(), appearance);
+        }
+        let mut materials: Vec<&str> = WAND_APPEARANCES.to_vec();
+        rand::thread_rng().shuffle(&mut materials);
+        for &effect in WAND_EFFECTS.iter() {
+            let key = kind_key(&Item::Wand { effect, charges: 0 }).unwrap();
+            appearances.insert(key, format!("{} wand", materials.pop().unwrap()));
+        }
+        Identification { appearances, identified: HashMap::new(), labels: HashMap::new() }
+    }
+
+    pub fn is_identified(&self, item: &Item) -> bool {
+        kind_key(item).map_or(true, |key| self.identified.contains_key(&key))
+    }
+
+    /// The name the player knows the item by. Once a kind of wand is identified, that includes its charges.
+    pub fn name_of(&self, entity: &Entity) -> String {
+        let key = match entity.item.as_ref().and_then(kind_key) {
+            Some(key) if !self.identified.contains_key(&key) => key,
+            _ => return known_name(entity),
+        };
+        let appearance = self.appearances.get(&key).cloned().unwrap_or_else(|| entity.name.clone());
+        match self.labels.get(&key) {
+            Some(label) => format!("{} called {}", appearance, label),
+            None => appearance,
+        }
+    }
+
+    /// The name the player knows the item by, along with how many there are if it's a stack: "3 health potions".
+    pub fn describe(&self, entity: &Entity) -> String {
+        let name = self.name_of(entity);
+        if entity.quantity > 1 {
+            format!("{} {}", entity.quantity, plural(&name))
+        } else {
+            name
+        }
+    }
+
+    /// Learns what every item of this one's kind is. Returns false if there was nothing new to learn.
+    pub fn identify(&mut self, entity: &Entity) -> bool {
+        match entity.item.as_ref().and_then(kind_key) {
+            Some(key) if !self.identified.contains_key(&key) => {
+                self.labels.remove(&key);
+                self.identified.insert(key, entity.name.clone());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Calls every item of this kind by the player's own name for it; an empty label clears it.
+    pub fn label(&mut self, item: &Item, label: &str) {
+        if let Some(key) = kind_key(item) {
+            if label.is_empty() {
+                self.labels.remove(&key);
+            } else {
+                self.labels.insert(key, label.to_string());
+            }
+        }
+    }
+
+    /// Everything the player has worked out so far, as "appearance: real name".
+    pub fn discoveries(&self) -> Vec<String> {
+        let mut discoveries: Vec<String> = self.identified.iter()
+            .map(|(key, name)| match self.appearances.get(key) {
+                Some(appearance) => format!("{}: {}", appearance, name),
+                None => name.clone(),
+            })
+            .collect();
+        discoveries.sort();
+        discoveries
+    }
+}
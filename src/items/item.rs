@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::items::wand::WandEffect;
+
 //parameters for items
 pub const HEAL_AMOUNT: i32 = 4;
 pub const LIGHTNING_DAMAGE: i32 = 40;
@@ -8,6 +10,18 @@ pub const CONFUSE_RANGE: i32 = 8;
 pub const CONFUSE_NUM_TURNS: i32 = 10;
 pub const FIREBALL_RADIUS: i32 = 3;
 pub const FIREBALL_DAMAGE: i32 = 12;
+pub const FIREBALL_FLASH_TURNS: i32 = 2;
+pub const FIREBALL_BURN_TURNS: i32 = 3;
+pub const FIREBALL_BURN_DAMAGE: i32 = 2;
+pub const TAME_RANGE: i32 = 5;
+pub const RAISE_RANGE: i32 = 5;
+// a meal leaves you regenerating for a while
+pub const MEAL_REGEN_TURNS: i32 = 10;
+pub const MEAL_REGEN_AMOUNT: i32 = 1;
+pub const ROTTEN_POISON_TURNS: i32 = 8;
+pub const ROTTEN_POISON_DAMAGE: i32 = 1;
+// anything but wildlife has to be beaten down below this percentage of its hp before it will submit
+pub const TAME_HP_PERCENT: i32 = 50;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Item {
@@ -15,9 +29,59 @@ pub enum Item {
     Lightning,
     Confuse,
     Fireball,
+    RaiseSkeleton,
+    Tame,
+    Identify,
+    Recharge,
+    Wand { effect: WandEffect, charges: i32 },
+    Corpse { nutrition: i32, turns_left: i32 },
+    Ration,
     Artifact {name: String, value: i32},
+    // a pile of coins, as many as its quantity; picking it up puts it straight in the purse
+    Gold,
     Sword,
     Shield,
+    Greataxe,
+    Helmet,
+    LeatherArmor,
+    Cloak,
+    Gauntlets,
+    Boots,
+    Ring,
+    Amulet,
+    Bow,
+    Crossbow,
+    ThrowingKnife,
+    Arrow,
+    Bolt,
+}
+
+impl Item {
+    /// Whether several of these can share an inventory slot. Anything with a state of its own, like a weapon
+    /// or a corpse that rots at its own pace, has to be kept apart.
+    pub fn is_stackable(&self) -> bool {
+        use Item::*;
+        match self {
+            Heal | Lightning | Confuse | Fireball | RaiseSkeleton | Tame | Identify | Recharge | Ration | Arrow | Bolt => true,
+            _ => false,
+        }
+    }
+
+    /// How heavy one of these is, which decides how far it can be thrown and how much it hurts when it lands.
+    pub fn weight(&self) -> i32 {
+        use Item::*;
+        match self {
+            Lightning | Confuse | Fireball | RaiseSkeleton | Tame | Identify | Recharge | Gold | Arrow | Bolt | Ring | Amulet => 0,
+            Heal | Ration | Wand { .. } | ThrowingKnife => 1,
+            Cloak | Gauntlets | Boots | Bow => 2,
+            Artifact { .. } | Helmet => 3,
+            Sword => 4,
+            Crossbow => 5,
+            Shield => 6,
+            Greataxe | LeatherArmor => 8,
+            Corpse { .. } => 10,
+        }
+    }
 }
 
 pub enum UseResult {
@@ -0,0 +1,174 @@
+use rand::Rng;
+
+use crate::{Entity, IndependentSample, Transition, Weighted, WeightedChoice};
+use crate::entities::monster::Monster;
+use crate::inventory::inventory_actions::get_equipped_id_in_slot;
+use crate::items::affix::enchant;
+use crate::items::item::Item;
+use crate::items::shop::gold_amount;
+use crate::items::wand::WandEffect;
+use crate::map::mapgen::{from_dungeon_level, make_item};
+
+/// An item a monster might be carrying, with its weight at each depth.
+pub struct LootDrop {
+    pub item: Item,
+    pub weight: &'static [Transition],
+}
+
+/// What a monster spawns carrying: everything in `guaranteed`, plus a `chance` in percent of one more item
+/// picked from `drops`. It all ends up on the floor when the monster dies.
+pub struct LootTable {
+    pub guaranteed: &'static [Item],
+    pub chance: i32,
+    pub drops: &'static [LootDrop],
+}
+
+pub fn loot_table(monster: Monster) -> &'static LootTable {
+    match monster {
+        Monster::Orc => &ORC_LOOT,
+        Monster::OrcCaptain => &ORC_CAPTAIN_LOOT,
+        Monster::Kobold => &KOBOLD_LOOT,
+        Monster::GoblinArcher => &GOBLIN_ARCHER_LOOT,
+        Monster::OrcShaman => &ORC_SHAMAN_LOOT,
+        Monster::CaveBeetle => &NO_LOOT,
+        Monster::Troll => &TROLL_LOOT,
+        Monster::Skeleton => &SKELETON_LOOT,
+        Monster::SkeletonCaptain => &SKELETON_CAPTAIN_LOOT,
+        Monster::Spectre => &SPECTRE_LOOT,
+        Monster::Mercenary => &NO_LOOT,
+    }
+}
+
+/// Fills the monster's pack from its loot table for the given depth. Any equipment it gets is put to use
+/// if the monster has that slot free, so a well-armed monster hits harder or is harder to hit, and its hp
+/// is topped up to whatever maximum that leaves it with.
+pub fn carry_loot(monster: &mut Entity, table: &LootTable, level: u32, generated_uniques: &mut Vec<String>) {
+    let mut items: Vec<Item> = table.guaranteed.to_vec();
+    if rand::thread_rng().gen_range(0, 100) < table.chance {
+        let mut chances: Vec<Weighted<Item>> = table.drops.iter()
+            .map(|drop| Weighted { weight: from_dungeon_level(drop.weight, level), item: drop.item.clone() })
+            .filter(|chance| chance.weight > 0)
+            .collect();
+        // nothing on the table might be deep enough to turn up yet
+        if !chances.is_empty() {
+            items.push(WeightedChoice::new(&mut chances).ind_sample(&mut rand::thread_rng()));
+        }
+    }
+    for item in items {
+        let mut object = make_item(item, monster.x, monster.y);
+        enchant(&mut object, level, generated_uniques);
+        if object.item == Some(Item::Gold) {
+            object.quantity = gold_amount(level);
+        }
+        if let Some(equipment) = object.equipment.as_mut() {
+            if get_equipped_id_in_slot(equipment.slot, &monster.inventory).is_none() {
+                equipment.equipped = true;
+            }
+        }
+        monster.inventory.push(object);
+    }
+    // gear that adds to max hp shouldn't leave the monster starting out wounded
+    let max_hp = monster.max_hp();
+    if let Some(fighter) = monster.fighter.as_mut() {
+        fighter.hp = max_hp;
+    }
+}
+
+pub const NO_LOOT: LootTable = LootTable { guaranteed: &[], chance: 0, drops: &[] };
+
+pub const ORC_LOOT: LootTable = LootTable {
+    guaranteed: &[],
+    chance: 30,
+    drops: &[
+        LootDrop { item: Item::Heal, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::Ration, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::Gold, weight: &[Transition { level: 1, value: 15 }] },
+        LootDrop { item: Item::Sword, weight: &[Transition { level: 3, value: 5 }] },
+        LootDrop { item: Item::Shield, weight: &[Transition { level: 5, value: 3 }] },
+    ],
+};
+
+pub const ORC_CAPTAIN_LOOT: LootTable = LootTable {
+    guaranteed: &[Item::Heal],
+    chance: 100,
+    drops: &[
+        LootDrop { item: Item::Sword, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::Shield, weight: &[Transition { level: 1, value: 5 }, Transition { level: 5, value: 10 }] },
+        LootDrop { item: Item::Helmet, weight: &[Transition { level: 1, value: 5 }] },
+        LootDrop { item: Item::Gold, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::Artifact { name: String::new(), value: 0 }, weight: &[Transition { level: 4, value: 3 }] },
+    ],
+};
+
+pub const KOBOLD_LOOT: LootTable = LootTable {
+    guaranteed: &[],
+    chance: 40,
+    drops: &[
+        LootDrop { item: Item::ThrowingKnife, weight: &[Transition { level: 1, value: 5 }] },
+        LootDrop { item: Item::Heal, weight: &[Transition { level: 1, value: 5 }] },
+        LootDrop { item: Item::Tame, weight: &[Transition { level: 2, value: 2 }] },
+        LootDrop { item: Item::Gold, weight: &[Transition { level: 1, value: 10 }] },
+    ],
+};
+
+pub const GOBLIN_ARCHER_LOOT: LootTable = LootTable {
+    guaranteed: &[],
+    chance: 25,
+    drops: &[
+        LootDrop { item: Item::Arrow, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::Heal, weight: &[Transition { level: 1, value: 5 }] },
+    ],
+};
+
+pub const ORC_SHAMAN_LOOT: LootTable = LootTable {
+    guaranteed: &[],
+    chance: 60,
+    drops: &[
+        LootDrop { item: Item::Heal, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::Confuse, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::Fireball, weight: &[Transition { level: 4, value: 5 }] },
+        LootDrop { item: Item::Wand { effect: WandEffect::Confusion, charges: 0 }, weight: &[Transition { level: 3, value: 3 }] },
+    ],
+};
+
+pub const TROLL_LOOT: LootTable = LootTable {
+    guaranteed: &[],
+    chance: 50,
+    drops: &[
+        LootDrop { item: Item::Heal, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::Shield, weight: &[Transition { level: 1, value: 5 }] },
+        LootDrop { item: Item::Greataxe, weight: &[Transition { level: 5, value: 5 }] },
+        LootDrop { item: Item::Gold, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::Artifact { name: String::new(), value: 0 }, weight: &[Transition { level: 6, value: 3 }] },
+    ],
+};
+
+pub const SKELETON_LOOT: LootTable = LootTable {
+    guaranteed: &[],
+    chance: 25,
+    drops: &[
+        LootDrop { item: Item::Sword, weight: &[Transition { level: 1, value: 5 }] },
+        LootDrop { item: Item::Shield, weight: &[Transition { level: 1, value: 5 }] },
+        LootDrop { item: Item::LeatherArmor, weight: &[Transition { level: 2, value: 3 }] },
+        LootDrop { item: Item::RaiseSkeleton, weight: &[Transition { level: 3, value: 2 }] },
+    ],
+};
+
+pub const SKELETON_CAPTAIN_LOOT: LootTable = LootTable {
+    guaranteed: &[Item::Sword],
+    chance: 60,
+    drops: &[
+        LootDrop { item: Item::Shield, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::RaiseSkeleton, weight: &[Transition { level: 3, value: 5 }] },
+        LootDrop { item: Item::Artifact { name: String::new(), value: 0 }, weight: &[Transition { level: 5, value: 3 }] },
+    ],
+};
+
+pub const SPECTRE_LOOT: LootTable = LootTable {
+    guaranteed: &[],
+    chance: 50,
+    drops: &[
+        LootDrop { item: Item::Lightning, weight: &[Transition { level: 1, value: 10 }] },
+        LootDrop { item: Item::Artifact { name: String::new(), value: 0 }, weight: &[Transition { level: 1, value: 5 }] },
+    ],
+};
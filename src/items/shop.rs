@@ -0,0 +1,143 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Entity, IndependentSample, Transition, Weighted, WeightedChoice};
+use crate::inventory::inventory_actions::add_to_inventory;
+use crate::items::affix::{enchant, Rarity};
+use crate::items::identification::Identification;
+use crate::items::item::Item;
+use crate::items::loot::LootDrop;
+use crate::items::wand::WandEffect;
+use crate::map::mapgen::{from_dungeon_level, make_item};
+
+// how much gold turns up in a pile, for every level of depth
+const GOLD_PER_LEVEL_MIN: i32 = 10;
+const GOLD_PER_LEVEL_MAX: i32 = 30;
+
+const STOCK_MIN: i32 = 6;
+const STOCK_MAX: i32 = 10;
+// how many of a stackable item a shop keeps on the shelf
+const STOCK_STACK_MAX: i32 = 3;
+
+// the most a shopkeeper charges over the odds, in percent
+const MAX_MARKUP: i32 = 30;
+
+const MERCENARY_BASE_COST: i32 = 300;
+const MERCENARY_COST_PER_LEVEL: i32 = 100;
+
+/// A shopkeeper's business. Everything for sale sits in the shopkeeper's own inventory, and each one
+/// charges a little over the odds on top of the going price.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Shop {
+    pub markup: i32,
+}
+
+impl Shop {
+    pub fn new() -> Self {
+        Shop { markup: rand::thread_rng().gen_range(0, MAX_MARKUP + 1) }
+    }
+
+    /// What the shopkeeper asks for one of the item.
+    pub fn asking_price(&self, item: &Entity, identification: &Identification) -> i32 {
+        price(item, identification) * (100 + self.markup) / 100
+    }
+}
+
+/// What one of the item is worth. Gear that's better than common is worth a good deal more, and an artifact
+/// is worth whatever value it was found with. A wand's charges only count once the player knows what kind
+/// of wand it is, so the price can't give away how many it holds.
+pub fn price(item: &Entity, identification: &Identification) -> i32 {
+    use Item::*;
+    let base = match item.item.as_ref() {
+        Some(Heal) | Some(Ration) => 50,
+        Some(Confuse) | Some(Identify) => 75,
+        Some(Lightning) | Some(Tame) => 120,
+        Some(Fireball) | Some(RaiseSkeleton) | Some(Recharge) => 200,
+        Some(wand @ Wand { .. }) if !identification.is_identified(wand) => 100,
+        Some(Wand { charges, .. }) => 100 + 40 * charges,
+        Some(Artifact { value, .. }) => return *value,
+        Some(Sword) | Some(Bow) => 150,
+        Some(Shield) | Some(Helmet) | Some(Boots) | Some(Cloak) | Some(ThrowingKnife) => 80,
+        Some(LeatherArmor) | Some(Gauntlets) => 120,
+        Some(Greataxe) | Some(Crossbow) => 300,
+        Some(Ring) | Some(Amulet) => 400,
+        Some(Arrow) | Some(Bolt) => 5,
+        Some(Gold) => 1,
+        Some(Corpse { .. }) | None => 0,
+    };
+    base * item.equipment.map_or(1, |e| rarity_multiplier(e.rarity))
+}
+
+/// What a shopkeeper pays for one of the item. They'll only give half of what it's worth, except for
+/// artifacts, which collectors will always pay full value for.
+pub fn sale_price(item: &Entity, identification: &Identification) -> i32 {
+    match item.item {
+        Some(Item::Artifact { value, .. }) => value,
+        _ => price(item, identification) / 2,
+    }
+}
+
+fn rarity_multiplier(rarity: Rarity) -> i32 {
+    match rarity {
+        Rarity::Common => 1,
+        Rarity::Magic => 2,
+        Rarity::Rare => 4,
+        Rarity::Unique => 10,
+    }
+}
+
+/// How many coins are in a pile of gold found at the given depth.
+pub fn gold_amount(level: u32) -> i32 {
+    rand::thread_rng().gen_range(GOLD_PER_LEVEL_MIN, GOLD_PER_LEVEL_MAX + 1) * level as i32
+}
+
+/// What it costs to hire a mercenary at the given depth; the deeper the dungeon, the more they want.
+pub fn mercenary_cost(level: u32) -> i32 {
+    MERCENARY_BASE_COST + MERCENARY_COST_PER_LEVEL * level as i32
+}
+
+/// Fills the shopkeeper's shelves with stock for the given depth. Deeper shops carry stronger items,
+/// and their gear is as likely to be enchanted as anything found lying around at that depth.
+pub fn stock_shop(shopkeeper: &mut Entity, level: u32, generated_uniques: &mut Vec<String>) {
+    let mut chances: Vec<Weighted<Item>> = SHOP_STOCK.iter()
+        .map(|drop| Weighted { weight: from_dungeon_level(drop.weight, level), item: drop.item.clone() })
+        .filter(|chance| chance.weight > 0)
+        .collect();
+    let choice = WeightedChoice::new(&mut chances);
+    for _ in 0..rand::thread_rng().gen_range(STOCK_MIN, STOCK_MAX + 1) {
+        let mut object = make_item(choice.ind_sample(&mut rand::thread_rng()), shopkeeper.x, shopkeeper.y);
+        enchant(&mut object, level, generated_uniques);
+        if object.item.as_ref().map_or(false, |item| item.is_stackable()) {
+            object.quantity = rand::thread_rng().gen_range(1, STOCK_STACK_MAX + 1);
+        }
+        add_to_inventory(&mut shopkeeper.inventory, object);
+    }
+}
+
+pub const SHOP_STOCK: &[LootDrop] = &[
+    LootDrop { item: Item::Heal, weight: &[Transition { level: 1, value: 20 }] },
+    LootDrop { item: Item::Ration, weight: &[Transition { level: 1, value: 15 }] },
+    LootDrop { item: Item::Identify, weight: &[Transition { level: 1, value: 10 }] },
+    LootDrop { item: Item::Confuse, weight: &[Transition { level: 1, value: 8 }] },
+    LootDrop { item: Item::Lightning, weight: &[Transition { level: 3, value: 8 }] },
+    LootDrop { item: Item::Tame, weight: &[Transition { level: 2, value: 5 }] },
+    LootDrop { item: Item::Fireball, weight: &[Transition { level: 5, value: 6 }] },
+    LootDrop { item: Item::Recharge, weight: &[Transition { level: 3, value: 4 }] },
+    LootDrop { item: Item::Wand { effect: WandEffect::Confusion, charges: 0 }, weight: &[Transition { level: 2, value: 3 }] },
+    LootDrop { item: Item::Wand { effect: WandEffect::Lightning, charges: 0 }, weight: &[Transition { level: 3, value: 3 }] },
+    LootDrop { item: Item::Wand { effect: WandEffect::Fireball, charges: 0 }, weight: &[Transition { level: 6, value: 2 }] },
+    LootDrop { item: Item::Arrow, weight: &[Transition { level: 1, value: 8 }] },
+    LootDrop { item: Item::Bolt, weight: &[Transition { level: 4, value: 6 }] },
+    LootDrop { item: Item::Sword, weight: &[Transition { level: 1, value: 6 }] },
+    LootDrop { item: Item::Shield, weight: &[Transition { level: 1, value: 6 }] },
+    LootDrop { item: Item::Helmet, weight: &[Transition { level: 1, value: 5 }] },
+    LootDrop { item: Item::LeatherArmor, weight: &[Transition { level: 2, value: 5 }] },
+    LootDrop { item: Item::Boots, weight: &[Transition { level: 2, value: 4 }] },
+    LootDrop { item: Item::Gauntlets, weight: &[Transition { level: 3, value: 4 }] },
+    LootDrop { item: Item::Cloak, weight: &[Transition { level: 3, value: 4 }] },
+    LootDrop { item: Item::Bow, weight: &[Transition { level: 2, value: 4 }] },
+    LootDrop { item: Item::Crossbow, weight: &[Transition { level: 5, value: 3 }] },
+    LootDrop { item: Item::Greataxe, weight: &[Transition { level: 5, value: 3 }] },
+    LootDrop { item: Item::Ring, weight: &[Transition { level: 4, value: 2 }] },
+    LootDrop { item: Item::Amulet, weight: &[Transition { level: 6, value: 2 }] },
+];
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::damage::{Damage, DamageType};
+use crate::items::item::Item;
+
+// how many charges a wand is found with
+pub const WAND_CHARGES_MIN: i32 = 3;
+pub const WAND_CHARGES_MAX: i32 = 7;
+// how many charges a scroll of recharging adds
+pub const RECHARGE_MIN: i32 = 2;
+pub const RECHARGE_MAX: i32 = 5;
+// the chance in percent of a wand blowing up when it's recharged, plus more for every charge it still holds
+pub const RECHARGE_EXPLODE_CHANCE: i32 = 10;
+pub const RECHARGE_EXPLODE_CHANCE_PER_CHARGE: i32 = 5;
+// a wand that blows up hurts more the more charges it was holding
+pub const WAND_EXPLOSION_DAMAGE: i32 = 5;
+pub const WAND_EXPLOSION_DAMAGE_PER_CHARGE: i32 = 2;
+
+/// The spell a wand casts. Each one works just like the scroll of the same name, except that the wand
+/// only loses a charge rather than being used up.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WandEffect {
+    Lightning,
+    Fireball,
+    Confusion,
+}
+
+pub const WAND_EFFECTS: [WandEffect; 3] = [WandEffect::Lightning, WandEffect::Fireball, WandEffect::Confusion];
+
+impl WandEffect {
+    // what the wand's stored magic does to whoever is holding it when it goes off all at once
+    pub fn damage_type(self) -> DamageType {
+        match self {
+            WandEffect::Lightning => DamageType::Lightning,
+            WandEffect::Fireball => DamageType::Fire,
+            WandEffect::Confusion => DamageType::Physical,
+        }
+    }
+}
+
+impl std::fmt::Display for WandEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            WandEffect::Lightning => "lightning",
+            WandEffect::Fireball => "fireball",
+            WandEffect::Confusion => "confusion",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Uses up one of the wand's charges and returns how many it has left. Anything that isn't a wand has none.
+pub fn spend_charge(item: &mut Item) -> i32 {
+    match item {
+        Item::Wand { charges, .. } => {
+            *charges -= 1;
+            *charges
+        }
+        _ => 0,
+    }
+}
+
+/// Puts more charges into a wand. Anything else is left alone.
+pub fn add_charges(item: &mut Item, added: i32) {
+    if let Item::Wand { charges, .. } = item {
+        *charges += added;
+    }
+}
+
+/// The chance in percent that a wand still holding `charges` blows up when it's recharged.
+pub fn recharge_explode_chance(charges: i32) -> i32 {
+    RECHARGE_EXPLODE_CHANCE + RECHARGE_EXPLODE_CHANCE_PER_CHARGE * charges
+}
+
+/// What a wand still holding `charges` does to whoever is holding it when it blows up.
+pub fn explosion_damage(effect: WandEffect, charges: i32) -> Damage {
+    Damage::new(WAND_EXPLOSION_DAMAGE + WAND_EXPLOSION_DAMAGE_PER_CHARGE * charges, effect.damage_type())
+}
@@ -7,13 +7,13 @@ use log::LevelFilter;
 use rand::distributions::{IndependentSample, Weighted, WeightedChoice};
 use simple_logger::SimpleLogger;
 // use tcod::console::*;
-// use tcod::map::Map as FovMap;
 
 use entities::entity::Entity;
 use events::audio_event_processor::AudioEventProcessor;
 use events::event_log_processor::EventLogProcessor;
 use events::game_occurrence::GameOccurrenceEventProcessor;
-use graphics::render_functions::{initialize_fov, menu, msgbox};
+use events::noise_event_processor::NoiseEventProcessor;
+use graphics::render_functions::{menu, msgbox};
 use map::mapgen::{in_map_bounds, make_map, MAP_HEIGHT, MAP_WIDTH};
 use map::mapgen::Map;
 use util::death_callback::DeathCallback;
@@ -32,10 +32,22 @@ mod events {
     pub mod audio_event_processor;
     pub mod game_occurrence;
     pub mod event_log_processor;
+    pub mod noise_event_processor;
 }
 mod entities {
     pub mod entity;
     pub mod fighter;
+    pub mod monster;
+    pub mod group;
+    pub mod boss;
+    pub mod faction;
+    pub mod companion;
+    pub mod hunger;
+    pub mod damage;
+    pub mod combat;
+    pub mod ranged;
+    pub mod awareness;
+    pub mod status_effect;
     pub mod equipment;
     pub mod slot;
     pub mod entity_actions;
@@ -47,6 +59,10 @@ mod map {
     pub mod mapgen;
     pub mod tile;
     pub mod map_functions;
+    pub mod fov;
+    pub mod lighting;
+    pub mod hazard;
+    pub mod noise;
 }
 mod graphics {
     pub mod camera;
@@ -55,9 +71,17 @@ mod graphics {
 
 mod inventory {
     pub mod inventory_actions;
+    pub mod equipment_screen;
+    pub mod shop_screen;
 }
 mod items {
     pub mod item;
+    pub mod loot;
+    pub mod corpse;
+    pub mod identification;
+    pub mod affix;
+    pub mod shop;
+    pub mod wand;
 }
 mod game_engine;
 mod framework;
@@ -73,6 +97,20 @@ mod util {
     pub mod namegen;
     pub mod messages;
     pub mod mut_two;
+    pub mod line;
+}
+
+#[cfg(test)]
+mod test {
+    mod test_combat;
+    mod test_damage;
+    mod test_equipment;
+    mod test_fov;
+    mod test_identification;
+    mod test_inventory;
+    mod test_noise;
+    mod test_throwing;
+    mod test_wand;
 }
 
 const SCREEN_WIDTH: i32 = 80;
@@ -128,7 +166,6 @@ fn main() -> BError{
     //     root,
     //     con: Offscreen::new(MAP_WIDTH, MAP_HEIGHT),
     //     panel: Offscreen::new(SCREEN_WIDTH, PANEL_HEIGHT),
-    //     fov: FovMap::new(MAP_WIDTH, MAP_HEIGHT),
     //     key: Default::default(),                    // default is a trait that can be implemented that gives an object default values
     //     mouse: Default::default()
     // };
@@ -0,0 +1,153 @@
+use crate::map::mapgen::{in_map_bounds, Map, MAP_HEIGHT, MAP_WIDTH};
+
+// multipliers for transforming coordinates into each of the 8 octants
+const OCTANTS: [[i32; 8]; 4] = [
+    [1, 0, 0, -1, -1, 0, 0, 1],
+    [0, 1, -1, 0, 0, -1, 1, 0],
+    [0, 1, 1, 0, 0, -1, -1, 0],
+    [1, 0, 0, 1, -1, 0, 0, -1],
+];
+
+/// Field of view computed directly from the dungeon `Map` using recursive shadowcasting.
+/// Used for the player's sight, monster sight and light sources alike. It can cover the whole map,
+/// or just a window of it when nothing beyond a short radius matters.
+#[derive(Clone, Debug)]
+pub struct FovMap {
+    // map coordinates of the window's top left corner
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+    visible: Vec<bool>,
+}
+
+impl Default for FovMap {
+    fn default() -> Self {
+        FovMap::new(MAP_WIDTH, MAP_HEIGHT)
+    }
+}
+
+impl FovMap {
+    pub fn new(width: i32, height: i32) -> Self {
+        FovMap {
+            left: 0,
+            top: 0,
+            width,
+            height,
+            visible: vec![false; (width * height) as usize],
+        }
+    }
+
+    /// A field of view just big enough to hold everything within `radius` of (x, y), clipped to the map.
+    pub fn around(x: i32, y: i32, radius: i32) -> Self {
+        let (left, top) = ((x - radius).max(0), (y - radius).max(0));
+        let (right, bottom) = ((x + radius).min(MAP_WIDTH - 1), (y + radius).min(MAP_HEIGHT - 1));
+        let (width, height) = ((right - left + 1).max(0), (bottom - top + 1).max(0));
+        FovMap {
+            left,
+            top,
+            width,
+            height,
+            visible: vec![false; (width * height) as usize],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.visible.iter_mut().for_each(|v| *v = false);
+    }
+
+    pub fn is_in_fov(&self, x: i32, y: i32) -> bool {
+        self.in_bounds(x, y) && self.visible[self.index(x, y)]
+    }
+
+    /// Recomputes which tiles are visible from (x, y). A radius of 0 or less means unlimited range.
+    pub fn compute_fov(&mut self, map: &Map, x: i32, y: i32, radius: i32, light_walls: bool) {
+        self.clear();
+        if !self.in_bounds(x, y) {
+            return;
+        }
+        let radius = if radius > 0 { radius } else { self.width.max(self.height) };
+        self.set_visible(x, y);
+        for octant in 0..8 {
+            self.cast_light(
+                map, x, y, 1, 1.0, 0.0, radius, light_walls,
+                (OCTANTS[0][octant], OCTANTS[1][octant], OCTANTS[2][octant], OCTANTS[3][octant]),
+            );
+        }
+    }
+
+    fn in_bounds(&self, x: i32, y: i32) -> bool {
+        self.left <= x && x < self.left + self.width && self.top <= y && y < self.top + self.height
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        ((x - self.left) + (y - self.top) * self.width) as usize
+    }
+
+    fn set_visible(&mut self, x: i32, y: i32) {
+        if self.in_bounds(x, y) {
+            let index = self.index(x, y);
+            self.visible[index] = true;
+        }
+    }
+
+    // scans one octant row by row, recursing whenever a wall splits the visible arc in two
+    #[allow(clippy::too_many_arguments)]
+    fn cast_light(
+        &mut self,
+        map: &Map,
+        origin_x: i32,
+        origin_y: i32,
+        row: i32,
+        mut start_slope: f32,
+        end_slope: f32,
+        radius: i32,
+        light_walls: bool,
+        (xx, xy, yx, yy): (i32, i32, i32, i32),
+    ) {
+        if start_slope < end_slope {
+            return;
+        }
+        let radius_squared = radius * radius;
+        let mut new_start = 0.0;
+        for j in row..=radius {
+            let dy = -j;
+            let mut blocked = false;
+            for dx in -j..=0 {
+                let x = origin_x + dx * xx + dy * xy;
+                let y = origin_y + dx * yx + dy * yy;
+                let left_slope = (dx as f32 - 0.5) / (dy as f32 + 0.5);
+                let right_slope = (dx as f32 + 0.5) / (dy as f32 - 0.5);
+                if start_slope < right_slope {
+                    continue;
+                } else if end_slope > left_slope {
+                    break;
+                }
+
+                let blocks_sight = !in_map_bounds(x, y) || map[x as usize][y as usize].block_sight;
+                if dx * dx + dy * dy < radius_squared && (light_walls || !blocks_sight) {
+                    self.set_visible(x, y);
+                }
+
+                if blocked {
+                    if blocks_sight {
+                        new_start = right_slope;
+                    } else {
+                        blocked = false;
+                        start_slope = new_start;
+                    }
+                } else if blocks_sight && j < radius {
+                    blocked = true;
+                    self.cast_light(
+                        map, origin_x, origin_y, j + 1, start_slope, left_slope, radius, light_walls,
+                        (xx, xy, yx, yy),
+                    );
+                    new_start = right_slope;
+                }
+            }
+            if blocked {
+                break;
+            }
+        }
+    }
+}
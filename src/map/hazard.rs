@@ -0,0 +1,91 @@
+use std::borrow::BorrowMut;
+
+use serde::{Deserialize, Serialize};
+use tcod::Color;
+use tcod::colors::{DARK_ORANGE, DARKER_YELLOW, LIGHT_GREY, ORANGE};
+
+use crate::entities::damage::{Damage, DamageType};
+use crate::entities::entity::Entity;
+use crate::game_engine::{GameEngine, PLAYER};
+use crate::map::lighting::is_visible_to_player;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum HazardKind {
+    Shockwave,
+    Hellfire,
+}
+
+impl HazardKind {
+    pub fn damage_type(self) -> DamageType {
+        match self {
+            HazardKind::Shockwave => DamageType::Physical,
+            HazardKind::Hellfire => DamageType::Fire,
+        }
+    }
+
+    // background of the tile while it's still a warning, and once it's gone off
+    pub fn colors(self) -> (Color, Color) {
+        match self {
+            HazardKind::Shockwave => (DARKER_YELLOW, LIGHT_GREY),
+            HazardKind::Hellfire => (DARK_ORANGE, ORANGE),
+        }
+    }
+}
+
+impl std::fmt::Display for HazardKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            HazardKind::Shockwave => write!(f, "shockwave"),
+            HazardKind::Hellfire => write!(f, "flames"),
+        }
+    }
+}
+
+/// A tile that's about to hurt, or is hurting, whoever stands on it. It's marked for `warning_turns` before it
+/// goes off, which is what gives the player a chance to get out of the way, then hurts for `active_turns`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Hazard {
+    pub x: i32,
+    pub y: i32,
+    pub kind: HazardKind,
+    pub damage: i32,
+    pub warning_turns: i32,
+    pub active_turns: i32,
+}
+
+impl Hazard {
+    pub fn new(x: i32, y: i32, kind: HazardKind, damage: i32, warning_turns: i32, active_turns: i32) -> Self {
+        Hazard { x, y, kind, damage, warning_turns, active_turns }
+    }
+
+    pub fn color(&self) -> Color {
+        let (warning, active) = self.kind.colors();
+        if self.warning_turns > 0 { warning } else { active }
+    }
+}
+
+/// Counts down the warnings, and hurts everything standing on a hazard that has gone off.
+pub fn tick_hazards(game: &mut GameEngine) {
+    let entities: &mut Vec<Entity> = game.entities.borrow_mut();
+    let event_bus = game.event_bus.borrow_mut();
+    let player_pos = entities[PLAYER].pos();
+    for hazard in game.hazards.iter_mut() {
+        if hazard.warning_turns > 0 {
+            hazard.warning_turns -= 1;
+            continue;
+        }
+        let damage = Damage::new(hazard.damage, hazard.kind.damage_type());
+        for (id, entity) in entities.iter_mut().enumerate() {
+            if entity.alive && entity.fighter.is_some() && entity.pos() == (hazard.x, hazard.y) {
+                if id == PLAYER {
+                    game.messages.add(format!("You are caught in the {} for {} hit points!", hazard.kind, entity.damage_after_resistances(damage)), ORANGE);
+                } else if is_visible_to_player(&game.fov, &game.light_map, player_pos, hazard.x, hazard.y) {
+                    game.messages.add(format!("The {} is caught in the {}.", entity.name, hazard.kind), ORANGE);
+                }
+                entity.take_damage(damage, None, event_bus);
+            }
+        }
+        hazard.active_turns -= 1;
+    }
+    game.hazards.retain(|h| h.warning_turns > 0 || h.active_turns > 0);
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use tcod::Color;
+
+use crate::entities::entity::Entity;
+use crate::map::fov::FovMap;
+use crate::map::mapgen::{Map, MAP_HEIGHT, MAP_WIDTH};
+
+// tiles with less light than this are too dark to make out
+pub const VISIBLE_LIGHT_THRESHOLD: f32 = 0.15;
+// how strongly the colour of a light tints the tiles it falls on
+const LIGHT_TINT: f32 = 0.35;
+
+/// Something that gives off light: a torch, a brazier, a glowing item...
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LightSource {
+    pub radius: i32,
+    pub color: Color,
+    pub intensity: f32,
+}
+
+/// A light that only exists for a few turns, like the flash of an explosion.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransientLight {
+    pub x: i32,
+    pub y: i32,
+    pub light: LightSource,
+    pub turns_remaining: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LightLevel {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+impl LightLevel {
+    pub fn brightness(&self) -> f32 {
+        self.r.max(self.g).max(self.b).min(1.0)
+    }
+}
+
+/// The combined light falling on every tile of the map.
+#[derive(Clone, Debug)]
+pub struct LightMap {
+    width: i32,
+    height: i32,
+    levels: Vec<LightLevel>,
+    // scratch space for working out which tiles each light reaches
+    fov: FovMap,
+}
+
+impl Default for LightMap {
+    fn default() -> Self {
+        LightMap::new(MAP_WIDTH, MAP_HEIGHT)
+    }
+}
+
+impl LightMap {
+    pub fn new(width: i32, height: i32) -> Self {
+        LightMap {
+            width,
+            height,
+            levels: vec![LightLevel::default(); (width * height) as usize],
+            fov: FovMap::new(width, height),
+        }
+    }
+
+    pub fn compute(&mut self, map: &Map, entities: &[Entity], transient_lights: &[TransientLight]) {
+        self.levels.iter_mut().for_each(|l| *l = LightLevel::default());
+        for entity in entities {
+            // carried items that glow light up whoever is holding them
+            let carried = entity.inventory.iter().filter_map(|item| item.light);
+            for light in entity.light.into_iter().chain(carried) {
+                self.add_light(map, entity.x, entity.y, light);
+            }
+        }
+        for transient in transient_lights {
+            self.add_light(map, transient.x, transient.y, transient.light);
+        }
+    }
+
+    fn add_light(&mut self, map: &Map, x: i32, y: i32, light: LightSource) {
+        self.fov.compute_fov(map, x, y, light.radius, true);
+        for ly in (y - light.radius)..=(y + light.radius) {
+            for lx in (x - light.radius)..=(x + light.radius) {
+                if !self.fov.is_in_fov(lx, ly) {
+                    continue;
+                }
+                let distance = (((lx - x).pow(2) + (ly - y).pow(2)) as f32).sqrt();
+                let falloff = (1.0 - distance / (light.radius + 1) as f32).max(0.0);
+                let strength = light.intensity * falloff;
+                let level = &mut self.levels[(lx + ly * self.width) as usize];
+                level.r += strength * light.color.r as f32 / 255.0;
+                level.g += strength * light.color.g as f32 / 255.0;
+                level.b += strength * light.color.b as f32 / 255.0;
+            }
+        }
+    }
+
+    pub fn light_at(&self, x: i32, y: i32) -> LightLevel {
+        if 0 <= x && x < self.width && 0 <= y && y < self.height {
+            self.levels[(x + y * self.width) as usize]
+        } else {
+            LightLevel::default()
+        }
+    }
+
+    pub fn is_lit(&self, x: i32, y: i32) -> bool {
+        self.light_at(x, y).brightness() >= VISIBLE_LIGHT_THRESHOLD
+    }
+
+    /// Blends between a tile's dark and lit colours by how much light is on it, tinted by the light's colour.
+    pub fn shade(&self, x: i32, y: i32, lit_color: Color, dark_color: Color) -> Color {
+        let level = self.light_at(x, y);
+        let brightness = level.brightness();
+        if brightness <= 0.0 {
+            return dark_color;
+        }
+        let max_channel = level.r.max(level.g).max(level.b);
+        let shade_channel = |lit: u8, dark: u8, channel: f32| {
+            let tint = 1.0 - LIGHT_TINT + LIGHT_TINT * channel / max_channel;
+            let target = lit as f32 * tint;
+            (dark as f32 + (target - dark as f32) * brightness).max(0.0).min(255.0) as u8
+        };
+        Color {
+            r: shade_channel(lit_color.r, dark_color.r, level.r),
+            g: shade_channel(lit_color.g, dark_color.g, level.g),
+            b: shade_channel(lit_color.b, dark_color.b, level.b),
+        }
+    }
+}
+
+/// A tile is visible to the player if it is in their field of view and either lit or right next to them.
+pub fn is_visible_to_player(fov: &FovMap, light_map: &LightMap, player_pos: (i32, i32), x: i32, y: i32) -> bool {
+    let (player_x, player_y) = player_pos;
+    let next_to_player = (x - player_x).abs() <= 1 && (y - player_y).abs() <= 1;
+    fov.is_in_fov(x, y) && (light_map.is_lit(x, y) || next_to_player)
+}
+
+pub fn tick_transient_lights(transient_lights: &mut Vec<TransientLight>) {
+    transient_lights.iter_mut().for_each(|l| l.turns_remaining -= 1);
+    transient_lights.retain(|l| l.turns_remaining > 0);
+}
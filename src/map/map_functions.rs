@@ -1,10 +1,10 @@
 use tcod::colors::{RED, VIOLET};
+use crate::entities::companion::{COMPANION_TRAVEL_RANGE, CompanionOrder};
 use crate::entities::entity::Entity;
 use crate::framework::Tcod;
-use crate::game_engine::{FOV_ALGO, FOV_LIGHT_WALLS, GameEngine, PLAYER, TORCH_RADIUS};
-use crate::map::mapgen::{from_dungeon_level, LEVEL_TYPE_TRANSITION, make_boss_map, make_map, Map};
+use crate::game_engine::{FOV_LIGHT_WALLS, GameEngine, PLAYER};
+use crate::map::mapgen::{from_dungeon_level, in_map_bounds, LEVEL_TYPE_TRANSITION, make_boss_map, make_map, Map};
 use crate::entities::entity_actions::target_tile;
-use crate::graphics::render_functions::initialize_fov;
 
 pub fn is_blocked(x: i32, y: i32, map: &Map, entity: &[Entity]) -> bool {
     if map[x as usize][y as usize].blocked {
@@ -15,28 +15,31 @@ pub fn is_blocked(x: i32, y: i32, map: &Map, entity: &[Entity]) -> bool {
         .any(|object| object.blocks && object.pos() == (x,y))
 }
 
-pub fn next_level(tcod: &mut Tcod, game: &mut GameEngine) {
+pub fn next_level(game: &mut GameEngine) {
     game.messages.add("You rest for a minute and recover your strength", VIOLET);
     let heal_hp = game.entities[PLAYER].max_hp() / 2;
     game.entities[PLAYER].heal(heal_hp);
     game.messages.add("You descend deeper into the dungeon ...", RED);
     game.dungeon_level += 1;
     let dungeon_level = game.dungeon_level;
+    let travellers = take_travelling_companions(game);
+    game.hazards.clear();
     game.map = match from_dungeon_level(LEVEL_TYPE_TRANSITION, dungeon_level) {
         0 => make_map(game, dungeon_level),
         1 => make_boss_map(game, dungeon_level),
         _ => make_map(game, dungeon_level),
     };
-    initialize_fov(tcod, &game.map);
-    tcod.fov.compute_fov(game.entities[PLAYER].x, game.entities[PLAYER].y, TORCH_RADIUS, FOV_LIGHT_WALLS, FOV_ALGO)
+    place_travelling_companions(travellers, game);
+    let (player_x, player_y) = game.entities[PLAYER].pos();
+    game.fov.compute_fov(&game.map, player_x, player_y, 0, FOV_LIGHT_WALLS)
 }
 
-pub fn closest_monster(tcod: &Tcod, game: &mut GameEngine, max_range: i32) -> Option<usize> {
+pub fn closest_monster(game: &mut GameEngine, max_range: i32) -> Option<usize> {
     let mut closest_enemy = None;
     let mut closest_dist = (max_range +1) as f32;
 
     for (id, object) in game.entities.iter().enumerate() {
-        if id != PLAYER && object.fighter.is_some() && object.ai.is_some() && tcod.fov.is_in_fov(object.x, object.y) {
+        if id != PLAYER && object.fighter.is_some() && game.entities[PLAYER].is_hostile_to(object) && game.is_visible(object.x, object.y) {
             let dist = game.entities[PLAYER].distance_to(object);
             if dist < closest_dist {
                 closest_enemy = Some(id);
@@ -47,6 +50,47 @@ pub fn closest_monster(tcod: &Tcod, game: &mut GameEngine, max_range: i32) -> Op
     closest_enemy
 }
 
+// pulls the companions that will follow the player down the stairs out of the level that's about to be thrown away
+fn take_travelling_companions(game: &mut GameEngine) -> Vec<Entity> {
+    let (player_x, player_y) = game.entities[PLAYER].pos();
+    let mut travellers = vec![];
+    let mut id = PLAYER + 1;
+    while id < game.entities.len() {
+        let entity = &game.entities[id];
+        let travels = entity.alive
+            && entity.companion.map_or(false, |c| c.order != CompanionOrder::Stay)
+            && entity.distance(player_x, player_y) <= COMPANION_TRAVEL_RANGE as f32;
+        if travels {
+            travellers.push(game.entities.swap_remove(id));
+        } else {
+            id += 1;
+        }
+    }
+    travellers
+}
+
+// sets the companions down in the free tiles nearest the player on the new level
+fn place_travelling_companions(travellers: Vec<Entity>, game: &mut GameEngine) {
+    let (player_x, player_y) = game.entities[PLAYER].pos();
+    for mut companion in travellers {
+        let spot = (1..=COMPANION_TRAVEL_RANGE)
+            .flat_map(|radius| (-radius..=radius).flat_map(move |dx| (-radius..=radius).map(move |dy| (dx, dy))))
+            .map(|(dx, dy)| (player_x + dx, player_y + dy))
+            .find(|&(x, y)| in_map_bounds(x, y) && !is_blocked(x, y, &game.map, &game.entities));
+        match spot {
+            Some((x, y)) => {
+                companion.set_pos(x, y);
+                if let Some(awareness) = companion.awareness.as_mut() {
+                    awareness.forget_target();
+                    awareness.wander_target = None;
+                }
+                game.entities.push(companion);
+            }
+            None => game.messages.add(format!("There's no room for your {} to follow you.", companion.name), RED),
+        }
+    }
+}
+
 pub fn target_monster(
     tcod: &mut Tcod,
     game: &mut GameEngine,
@@ -1,16 +1,30 @@
 use std::borrow::BorrowMut;
 use std::cmp;
 use rand::Rng;
-use tcod::colors::{DARK_CRIMSON, DARK_ORANGE, DARKER_AMBER, DARKER_AZURE, DESATURATED_GREEN, GOLD, LIGHT_RED, LIGHT_YELLOW, LIGHTEST_SEPIA, LIGHTEST_YELLOW, SKY, VIOLET, WHITE};
+use tcod::Color;
+use tcod::colors::{DARK_GREEN, DARK_RED, DARK_ORANGE, DARKER_AMBER, DARKER_AZURE, DESATURATED_GREEN, GOLD, LIGHT_SEPIA, LIGHT_VIOLET, LIGHTEST_SEPIA, LIGHTEST_YELLOW, SKY, VIOLET, WHITE};
 use crate::{Entity, GameEngine, IndependentSample, Transition, Weighted, WeightedChoice};
+use crate::entities::awareness::{AiState, Awareness};
+use crate::entities::boss::Boss;
+use crate::entities::combat::DamageRange;
+use crate::entities::damage::{DamageType, Resistances};
 use crate::entities::equipment::Equipment;
+use crate::entities::faction::Faction;
 use crate::entities::fighter::Fighter;
+use crate::entities::group::{GROUP_TEMPLATES, GroupMembership, GroupRole};
+use crate::entities::monster::Monster;
+use crate::entities::ranged::{AmmoType, RangedWeapon};
 use crate::entities::slot::Slot;
 use crate::game_engine::PLAYER;
+use crate::items::affix::{enchant, Rarity};
 use crate::items::item::Item;
+use crate::items::loot::{carry_loot, loot_table};
+use crate::items::shop::{gold_amount, Shop, stock_shop};
+use crate::items::wand::{WAND_CHARGES_MAX, WAND_CHARGES_MIN, WandEffect};
+use crate::map::lighting::LightSource;
 use crate::map::map_functions::is_blocked;
 use crate::map::tile::Tile;
-use crate::util::ai::Ai;
+use crate::util::ai::{Ai, MonsterSpell, SpellSlot};
 use crate::util::death_callback::DeathCallback;
 use crate::util::namegen;
 
@@ -28,6 +42,10 @@ const MAX_MONSTERS_TRANSITION: &[Transition] = &[
     Transition { level: 6, value: 5 },
 ];
 
+const CAVE_BEETLE_CHANCE_TRANSITION: &[Transition] = &[
+    Transition{ level: 1, value: 10 },
+];
+
 const TROLL_CHANCE_TRANSITION: &[Transition] = &[
     Transition{ level: 3, value: 15 },
     Transition{ level: 5, value: 30 },
@@ -46,22 +64,80 @@ const SPECTRE_CHANCE_TRANSITION: &[Transition] = &[
     Transition{ level: 10, value: 70 },
 ];
 
+const KOBOLD_CHANCE_TRANSITION: &[Transition] = &[
+    Transition{ level: 1, value: 20 },
+    Transition{ level: 4, value: 10 },
+];
+
+const GOBLIN_ARCHER_CHANCE_TRANSITION: &[Transition] = &[
+    Transition{ level: 2, value: 10 },
+    Transition{ level: 4, value: 20 },
+];
+
+const ORC_SHAMAN_CHANCE_TRANSITION: &[Transition] = &[
+    Transition{ level: 3, value: 5 },
+    Transition{ level: 6, value: 15 },
+];
+
+// chance in percent that a room holds a pack rather than individual monsters
+const GROUP_CHANCE_TRANSITION: &[Transition] = &[
+    Transition{ level: 2, value: 15 },
+    Transition{ level: 4, value: 25 },
+    Transition{ level: 7, value: 35 },
+];
+
+const GROUP_PLACEMENT_TRIES: i32 = 10;
+
+const ARCHER_ARROWS: i32 = 10;
+// chance in percent that a monster is asleep when the level is generated
+const ASLEEP_CHANCE: i32 = 40;
+
+const AMMO_BUNDLE_MIN: i32 = 3;
+const AMMO_BUNDLE_MAX: i32 = 8;
+
 const MAX_ITEMS_TRANSITION: &[Transition] = &[
     Transition{ level: 1, value: 1 },
     Transition{ level: 4, value: 2 },
 ];
 
+// chance in percent that a level has a shop in one of its rooms
+const SHOP_CHANCE_TRANSITION: &[Transition] = &[
+    Transition{ level: 2, value: 30 },
+    Transition{ level: 5, value: 45 },
+];
+
 const ROOM_OVERLAP_TRANSITION: &[Transition] = &[
     Transition{ level: 3, value: 1 },
 ];
 
+// chance for a room to be lit by braziers; deeper levels are darker
+const LIT_ROOM_CHANCE_TRANSITION: &[Transition] = &[
+    Transition{ level: 1, value: 60 },
+    Transition{ level: 4, value: 40 },
+    Transition{ level: 7, value: 25 },
+];
+
+const BRAZIER_LIGHT: LightSource = LightSource { radius: 7, color: Color { r: 255, g: 140, b: 50 }, intensity: 1.0 };
+// every scroll looks the same on the floor, so the glyph can't give away what an unidentified one is
+const SCROLL_COLOR: Color = LIGHTEST_YELLOW;
+
+const ARTIFACT_LIGHT: LightSource = LightSource { radius: 2, color: GOLD, intensity: 0.6 };
+
 pub const LEVEL_TYPE_TRANSITION: &[Transition] = &[
     Transition{ level: 1, value: 0 },
     Transition{ level: 2, value: 1 },
     Transition{ level: 3, value: 0 },
+    Transition{ level: 6, value: 1 },
+    Transition{ level: 7, value: 0 },
     Transition{ level: 10, value: 2 },
 ];
 
+// which boss holds the boss level: 0 for the Bone Lord, 1 for the Orc Warlord
+const BOSS_TRANSITION: &[Transition] = &[
+    Transition{ level: 1, value: 0 },
+    Transition{ level: 5, value: 1 },
+];
+
 pub type Map = Vec<Vec<Tile>>;
 
 pub fn in_map_bounds(x: i32, y: i32) -> bool {
@@ -108,6 +184,17 @@ fn create_room(room: Rect, map: &mut Map) {
     }
 }
 
+// braziers hang on the top and bottom walls of a room
+fn place_braziers(room: Rect, entities: &mut Vec<Entity>) {
+    let (center_x, _) = room.center();
+    for &y in [room.y1, room.y2].iter() {
+        let mut brazier = Entity::new(center_x, y, '*', DARK_ORANGE, "brazier", false);
+        brazier.light = Some(BRAZIER_LIGHT);
+        brazier.always_visible = true;
+        entities.push(brazier);
+    }
+}
+
 fn create_h_tunnel(x1: i32, x2: i32, y: i32, map: &mut Map) {
     for x in cmp::min(x1, x2)..(cmp::max(x1, x2) + 1) {
         map[x as usize][y as usize] = Tile::ground();
@@ -126,6 +213,7 @@ pub fn make_map(game :&mut GameEngine, level: u32) -> Map {
     // rng value ever time. So to fix this, I init everything to empty, then go back through and init with a
     // new map tile. It works, and since the surface chars are only decorative now, I guess its fine
     let entities: &mut Vec<Entity> = game.entities.borrow_mut();
+    let generated_uniques = &mut game.generated_uniques;
 
     let mut map = vec![vec![Tile::ground(); MAP_HEIGHT as usize]; MAP_WIDTH as usize];    // vec! is a shorthand macro that initializes the Vec and fills it with the specified value
     for x in 0..MAP_WIDTH as usize {
@@ -143,6 +231,8 @@ pub fn make_map(game :&mut GameEngine, level: u32) -> Map {
     assert_eq!(&entities[PLAYER] as *const _, &entities[0] as *const _);
     entities.truncate(1);
 
+    let mut shop_wanted = rand::thread_rng().gen_range(0, 100) < from_dungeon_level(SHOP_CHANCE_TRANSITION, level);
+
     for _ in 0..MAX_ROOMS {
         let w = rand::thread_rng().gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
         let h = rand::thread_rng().gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
@@ -159,7 +249,16 @@ pub fn make_map(game :&mut GameEngine, level: u32) -> Map {
 
         if !failed {
             create_room(new_room, &mut map);
-            place_objects(new_room, &map, entities, level);
+            // the player never starts out in a shop
+            if shop_wanted && !rooms.is_empty() {
+                place_shop(new_room, entities, level, generated_uniques);
+                shop_wanted = false;
+            } else {
+                place_objects(new_room, &map, entities, level, generated_uniques);
+                if rand::thread_rng().gen_range(0, 100) < from_dungeon_level(LIT_ROOM_CHANCE_TRANSITION, level) {
+                    place_braziers(new_room, entities);
+                }
+            }
 
             let (new_x, new_y) = new_room.center();
 
@@ -186,49 +285,105 @@ pub fn make_map(game :&mut GameEngine, level: u32) -> Map {
     map
 }
 
-pub fn make_boss_map(game: &mut GameEngine, _level: u32) -> Map {
+// a shop is kept well lit, with the shopkeeper tucked in a corner where they can't block the way through
+fn place_shop(room: Rect, entities: &mut Vec<Entity>, level: u32, generated_uniques: &mut Vec<String>) {
+    place_braziers(room, entities);
+    let mut shopkeeper = make_shopkeeper(room.x1 + 1, room.y1 + 1, level, generated_uniques);
+    shopkeeper.alive = true;
+    entities.push(shopkeeper);
+    log::debug!("placed a shop in the room at ({}, {})", room.x1, room.y1);
+}
+
+/// A shopkeeper with shelves stocked for the given depth. They never start a fight, but they're more than
+/// a match for anyone who tries to rob them.
+pub fn make_shopkeeper(x: i32, y: i32, level: u32, generated_uniques: &mut Vec<String>) -> Entity {
+    let mut shopkeeper = Entity::new(x, y, '@', GOLD, "Shopkeeper", true);
+    shopkeeper.fighter = Some(Fighter {base_max_hp: 80, hp: 80, base_defense: 4, base_power: 8, accuracy: 15, evasion: 10, stealth: 0, damage: DamageRange::new(2, 6), xp: 0, xp_reward: 300, damage_type: DamageType::Physical, resistances: Resistances::default(), on_death: DeathCallback::Monster });
+    shopkeeper.ai = Some(Ai::Basic);
+    shopkeeper.awareness = Some(Awareness::new(10));
+    shopkeeper.faction = Some(Faction::Merchant);
+    shopkeeper.shop = Some(Shop::new());
+    stock_shop(&mut shopkeeper, level, generated_uniques);
+    shopkeeper
+}
+
+pub fn make_boss_map(game: &mut GameEngine, level: u32) -> Map {
     let mut map = vec![vec![Tile::wall(); MAP_HEIGHT as usize]; MAP_WIDTH as usize];
     assert_eq!(&game.entities[PLAYER] as *const _, &game.entities[0] as *const _);
     game.entities.truncate(1);
 
     let boss_room = Rect::new(0, 0, MAP_WIDTH - 2, MAP_HEIGHT - 2);
     create_room(boss_room, &mut map);
+    place_braziers(Rect::new(boss_room.x1, boss_room.y1, boss_room.x2 / 2, boss_room.y2), &mut game.entities);
+    place_braziers(Rect::new(boss_room.x2 / 2, boss_room.y1, boss_room.x2 / 2, boss_room.y2), &mut game.entities);
 
     let (center_x, center_y) = boss_room.center();
 
     game.entities[PLAYER].set_pos(center_x, 3);
-    let mut boss = Entity::new(center_x, center_y, 'B', DARK_CRIMSON, "Boss", true);
-    boss.fighter = Some(Fighter {base_max_hp: 1, hp: 1, base_defense: 1, base_power: 1, xp: 1000, on_death: DeathCallback::Boss });
-    // boss.fighter = Some(Fighter {base_max_hp: 50, hp: 50, base_defense: 8, base_power: 11, xp: 1000, on_death: DeathCallback::Monster });
-    boss.ai = Some(Ai::Basic);
-    game.entities.push(boss);
+    let boss = match from_dungeon_level(BOSS_TRANSITION, level) {
+        0 => Boss::BoneLord,
+        _ => Boss::OrcWarlord,
+    };
+    game.entities.push(make_boss(boss, center_x, center_y));
     map
 }
 
-fn place_objects(room: Rect, map: &Map, objects: &mut Vec<Entity>, level: u32) {
+/// Builds the boss, carrying the loot it's guaranteed to drop, and at the head of a group so its summons rally to it.
+pub fn make_boss(boss: Boss, x: i32, y: i32) -> Entity {
+    let template = boss.template();
+    let mut entity = Entity::new(x, y, template.char, template.color, template.name, true);
+    entity.fighter = Some(template.fighter);
+    entity.faction = Some(template.faction);
+    entity.ai = Some(Ai::Boss { boss, phase: 0, cooldowns: vec![0; template.phases[0].abilities.len()] });
+    entity.awareness = Some(Awareness::new(MAP_WIDTH));
+    entity.group = Some(GroupMembership { group_id: rand::random(), role: GroupRole::Leader });
+    entity.inventory = template.loot.iter().map(|item| make_item(item.clone(), x, y)).collect();
+    entity
+}
+
+fn place_objects(room: Rect, map: &Map, objects: &mut Vec<Entity>, level: u32, generated_uniques: &mut Vec<String>) {
     let max_monsters = from_dungeon_level(MAX_MONSTERS_TRANSITION, level);
 
-    let num_monsters = rand::thread_rng().gen_range(0, max_monsters + 1);
+    // some rooms hold a whole pack instead of a few stragglers
+    let num_monsters = if rand::thread_rng().gen_range(0, 100) < from_dungeon_level(GROUP_CHANCE_TRANSITION, level) {
+        place_group(room, map, objects, level, generated_uniques);
+        0
+    } else {
+        rand::thread_rng().gen_range(0, max_monsters + 1)
+    };
 
-    let troll_chance = from_dungeon_level(TROLL_CHANCE_TRANSITION, level);
-    let skeleton_chance = from_dungeon_level(SKELETON_CHANCE_TRANSITION, level);
-    let spectre_chance = from_dungeon_level(SPECTRE_CHANCE_TRANSITION, level);
     let monster_chances = &mut [
         Weighted {
             weight: 80,
-            item: "orc",
+            item: Monster::Orc,
+        },
+        Weighted {
+            weight: from_dungeon_level(KOBOLD_CHANCE_TRANSITION, level),
+            item: Monster::Kobold
         },
         Weighted {
-            weight: troll_chance,
-            item: "troll"
+            weight: from_dungeon_level(GOBLIN_ARCHER_CHANCE_TRANSITION, level),
+            item: Monster::GoblinArcher
         },
         Weighted {
-            weight: skeleton_chance,
-            item: "skeleton"
+            weight: from_dungeon_level(ORC_SHAMAN_CHANCE_TRANSITION, level),
+            item: Monster::OrcShaman
         },
         Weighted {
-            weight: spectre_chance,
-            item: "spectre"
+            weight: from_dungeon_level(CAVE_BEETLE_CHANCE_TRANSITION, level),
+            item: Monster::CaveBeetle
+        },
+        Weighted {
+            weight: from_dungeon_level(TROLL_CHANCE_TRANSITION, level),
+            item: Monster::Troll
+        },
+        Weighted {
+            weight: from_dungeon_level(SKELETON_CHANCE_TRANSITION, level),
+            item: Monster::Skeleton
+        },
+        Weighted {
+            weight: from_dungeon_level(SPECTRE_CHANCE_TRANSITION, level),
+            item: Monster::Spectre
         }
     ];
     let monster_choice = WeightedChoice::new(monster_chances);
@@ -238,35 +393,9 @@ fn place_objects(room: Rect, map: &Map, objects: &mut Vec<Entity>, level: u32) {
         let y = rand::thread_rng().gen_range(room.y1 + 1, room.y2);
 
         if !is_blocked(x, y, map, objects) {
-            let mut monster = match monster_choice.ind_sample(&mut rand::thread_rng()) {
-                "skeleton" => {
-                    let mut skeleton = Entity::new(x, y, 's', LIGHTEST_SEPIA, "Skeleton", true);
-                    skeleton.fighter = Some(Fighter {base_max_hp: 25, hp: 25, base_defense: 1, base_power: 6, xp: 200, on_death: DeathCallback::Monster });
-                    skeleton.ai = Some(Ai::Basic);
-                    skeleton
-                },
-                "troll" => {
-                    let mut troll = Entity::new(x, y, 'T', DARKER_AMBER, "Troll", true);
-                    troll.fighter = Some(Fighter {base_max_hp: 30, hp: 30, base_defense: 2, base_power: 4, xp: 100, on_death: DeathCallback::Monster });
-                    troll.ai = Some(Ai::Basic);
-                    troll
-                },
-                "orc" => {
-                    let mut orc = Entity::new(x, y, 'o', DESATURATED_GREEN, "Orc", true);
-                    orc.fighter = Some(Fighter {base_max_hp: 10, hp: 10, base_defense: 0, base_power: 3, xp: 35, on_death: DeathCallback::Monster });
-                    orc.ai = Some(Ai::Basic);
-                    orc
-                },
-                "spectre" => {
-                    let mut orc = Entity::new(x, y, 'o', DARKER_AZURE, "Spectre", true);
-                    orc.fighter = Some(Fighter {base_max_hp: 43, hp: 43, base_defense: 4, base_power: 9, xp: 250, on_death: DeathCallback::Monster });
-                    orc.ai = Some(Ai::Basic);
-                    orc
-                },
-                _ => unreachable!()
-            };
-            monster.alive = true;
-            objects.push(monster);
+            let kind = monster_choice.ind_sample(&mut rand::thread_rng());
+            let asleep = rand::thread_rng().gen_range(0, 100) < ASLEEP_CHANCE;
+            spawn_monster(make_monster(kind, x, y), kind, level, asleep, objects, generated_uniques);
         }
     }
 
@@ -275,6 +404,10 @@ fn place_objects(room: Rect, map: &Map, objects: &mut Vec<Entity>, level: u32) {
             weight: 35,
             item: Item::Heal
         },
+        Weighted {
+            weight: 20,
+            item: Item::Ration
+        },
         Weighted {
             weight: from_dungeon_level(&[Transition{ level: 4, value:25 }], level),
             item: Item::Lightning
@@ -287,6 +420,38 @@ fn place_objects(room: Rect, map: &Map, objects: &mut Vec<Entity>, level: u32) {
             weight: from_dungeon_level(&[Transition{ level: 6, value:25 }], level),
             item: Item::Fireball
         },
+        Weighted {
+            weight: from_dungeon_level(&[Transition{ level: 3, value:5 }], level),
+            item: Item::RaiseSkeleton
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition{ level: 1, value:5 }, Transition{ level: 4, value:10 }], level),
+            item: Item::Tame
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition{ level: 1, value:7 }], level),
+            item: Item::Identify
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition{ level: 3, value:5 }], level),
+            item: Item::Recharge
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition{ level: 2, value:3 }, Transition{ level: 5, value:5 }], level),
+            item: Item::Wand { effect: WandEffect::Lightning, charges: 0 }
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition{ level: 2, value:4 }], level),
+            item: Item::Wand { effect: WandEffect::Confusion, charges: 0 }
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition{ level: 5, value:3 }, Transition{ level: 7, value:5 }], level),
+            item: Item::Wand { effect: WandEffect::Fireball, charges: 0 }
+        },
+        Weighted {
+            weight: 25,
+            item: Item::Gold
+        },
         Weighted {
             weight: from_dungeon_level(&[
                 Transition{ level: 2, value:0 }, Transition{ level: 2, value:5 }, Transition{ level: 5, value: 15 }
@@ -301,6 +466,58 @@ fn place_objects(room: Rect, map: &Map, objects: &mut Vec<Entity>, level: u32) {
         Weighted {
             weight: from_dungeon_level(&[Transition { level: 8, value: 15 }], level),
             item: Item::Shield
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 5, value: 4 }], level),
+            item: Item::Greataxe
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 2, value: 5 }], level),
+            item: Item::Helmet
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 2, value: 5 }], level),
+            item: Item::LeatherArmor
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 3, value: 4 }], level),
+            item: Item::Cloak
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 3, value: 4 }], level),
+            item: Item::Gauntlets
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 2, value: 4 }], level),
+            item: Item::Boots
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 4, value: 3 }], level),
+            item: Item::Ring
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 6, value: 2 }], level),
+            item: Item::Amulet
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 1, value: 5 }], level),
+            item: Item::ThrowingKnife
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 2, value: 5 }], level),
+            item: Item::Bow
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 5, value: 5 }], level),
+            item: Item::Crossbow
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 2, value: 15 }], level),
+            item: Item::Arrow
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 5, value: 10 }], level),
+            item: Item::Bolt
         }
     ];
     let item_choice = WeightedChoice::new(item_chances);
@@ -313,56 +530,354 @@ fn place_objects(room: Rect, map: &Map, objects: &mut Vec<Entity>, level: u32) {
         let y = rand::thread_rng().gen_range(room.y1 + 1, room.y2);
 
         if !is_blocked(x, y, map, objects) {
-            let mut item = match item_choice.ind_sample(&mut rand::thread_rng()) {
-                Item::Heal => {
-                    let mut object = Entity::new(x, y, '!', VIOLET, "health potion", false);
-                    object.item = Some(Item::Heal);
-                    object
-                },
-                Item::Lightning => {
-                    let mut object = Entity::new(x, y, '#', LIGHT_YELLOW, "scroll of lightning bolt", false);
-                    object.item = Some(Item::Lightning);
-                    object
-                },
-                Item::Fireball => {
-                    let mut object = Entity::new(x, y, '#', LIGHT_RED, "scroll of firball", false);
-                    object.item = Some(Item::Fireball);
-                    object
-                },
-                Item::Confuse => {
-                    let mut object = Entity::new(x, y, '#', LIGHTEST_YELLOW, "scroll of confusion", false);
-                    object.item = Some(Item::Confuse);
-                    object
-                },
-                Item::Artifact{name: _, value: _} => {
-                    let mut object = Entity::new(x, y, '{', GOLD, "artifact", false);
-                    object.item = Some(
-                        Item::Artifact{
-                            name: namegen::generate_artifact_name(2,7),
-                            value: 250 * rand::thread_rng().gen_range(1, 30)
-                        }
-                    );
-                    object
-                },
-                Item::Sword => {
-                    let mut object = Entity::new(x, y, '/', SKY, "sword", false);
-                    object.item = Some(Item::Sword);
-                    object.equipment = Some(Equipment{equipped: false, slot: Slot::RightHand, power_bonus: 3, defense_bonus: 0, max_hp_bonus: 0});
-                    object
-                },
-                Item::Shield => {
-                    let mut object = Entity::new(x, y, '[', DARK_ORANGE, "shield", false);
-                    object.item = Some(Item::Shield);
-                    object.equipment = Some(Equipment{equipped: false, slot: Slot::LeftHand, power_bonus: 0, defense_bonus: 1, max_hp_bonus: 0});
-                    object
-                }
+            let item = item_choice.ind_sample(&mut rand::thread_rng());
+            // ammunition is found in bundles
+            let count = match item {
+                Item::Arrow | Item::Bolt => rand::thread_rng().gen_range(AMMO_BUNDLE_MIN, AMMO_BUNDLE_MAX + 1),
+                Item::Gold => gold_amount(level),
+                _ => 1
             };
-            item.always_visible = true;
-            objects.push(item);
+            let mut object = make_item(item, x, y);
+            enchant(&mut object, level, generated_uniques);
+            object.quantity = count;
+            object.always_visible = true;
+            objects.push(object);
         }
     }
 }
 
+fn place_group(room: Rect, map: &Map, objects: &mut Vec<Entity>, level: u32, generated_uniques: &mut Vec<String>) {
+    let group_chances = &mut GROUP_TEMPLATES.iter()
+        .enumerate()
+        .map(|(id, template)| Weighted { weight: from_dungeon_level(&template.chance, level), item: id })
+        .collect::<Vec<_>>();
+    if group_chances.iter().all(|chance| chance.weight == 0) {
+        return;
+    }
+    let template = &GROUP_TEMPLATES[WeightedChoice::new(group_chances).ind_sample(&mut rand::thread_rng())];
+    let group_id = rand::random();
+    // the whole pack is either resting or on the move
+    let asleep = rand::thread_rng().gen_range(0, 100) < ASLEEP_CHANCE;
+
+    let mut roster = vec![(template.leader, GroupRole::Leader)];
+    for member in &template.members {
+        for _ in 0..rand::thread_rng().gen_range(member.min, member.max + 1) {
+            roster.push((member.monster, GroupRole::Member));
+        }
+    }
+    for (kind, role) in roster {
+        // in a crowded room the pack just comes out smaller, but a pack with no room for its leader doesn't come at all
+        let spot = (0..GROUP_PLACEMENT_TRIES)
+            .map(|_| (rand::thread_rng().gen_range(room.x1 + 1, room.x2), rand::thread_rng().gen_range(room.y1 + 1, room.y2)))
+            .find(|&(x, y)| !is_blocked(x, y, map, objects));
+        match spot {
+            Some((x, y)) => {
+                let mut monster = make_monster(kind, x, y);
+                monster.group = Some(GroupMembership { group_id, role });
+                spawn_monster(monster, kind, level, asleep, objects, generated_uniques);
+            }
+            None if role == GroupRole::Leader => return,
+            None => {}
+        }
+    }
+    log::debug!("placed a {} in the room at ({}, {})", template.name, room.x1, room.y1);
+}
+
+fn spawn_monster(mut monster: Entity, kind: Monster, level: u32, asleep: bool, objects: &mut Vec<Entity>, generated_uniques: &mut Vec<String>) {
+    carry_loot(&mut monster, loot_table(kind), level, generated_uniques);
+    if asleep {
+        if let Some(awareness) = monster.awareness.as_mut() {
+            awareness.state = AiState::Asleep;
+        }
+    }
+    monster.alive = true;
+    objects.push(monster);
+}
+
+pub fn make_monster(monster: Monster, x: i32, y: i32) -> Entity {
+    match monster {
+        Monster::Orc => {
+            let mut orc = Entity::new(x, y, 'o', DESATURATED_GREEN, "Orc", true);
+            orc.fighter = Some(Fighter {base_max_hp: 10, hp: 10, base_defense: 0, base_power: 3, accuracy: 0, evasion: 5, stealth: 0, damage: DamageRange::new(0, 2), xp: 0, xp_reward: 35, damage_type: DamageType::Physical, resistances: Resistances::default(), on_death: DeathCallback::Monster });
+            orc.ai = Some(Ai::Basic);
+            orc.awareness = Some(Awareness::new(8));
+            orc.faction = Some(Faction::Greenskins);
+            orc
+        },
+        Monster::OrcCaptain => {
+            let mut captain = Entity::new(x, y, 'O', DESATURATED_GREEN, "Orc Captain", true);
+            captain.fighter = Some(Fighter {base_max_hp: 20, hp: 20, base_defense: 1, base_power: 5, accuracy: 5, evasion: 5, stealth: 0, damage: DamageRange::new(1, 3), xp: 0, xp_reward: 120, damage_type: DamageType::Physical, resistances: Resistances::default(), on_death: DeathCallback::Monster });
+            captain.ai = Some(Ai::Basic);
+            captain.awareness = Some(Awareness::new(9));
+            captain.faction = Some(Faction::Greenskins);
+            captain
+        },
+        Monster::Kobold => {
+            let mut kobold = Entity::new(x, y, 'k', DARK_ORANGE, "Kobold", true);
+            kobold.fighter = Some(Fighter {base_max_hp: 7, hp: 7, base_defense: 0, base_power: 2, accuracy: 0, evasion: 10, stealth: 0, damage: DamageRange::new(0, 2), xp: 0, xp_reward: 20, damage_type: DamageType::Physical, resistances: Resistances::default(), on_death: DeathCallback::Monster });
+            kobold.ai = Some(Ai::Coward { flee_below_percent: 50 });
+            kobold.awareness = Some(Awareness::new(8));
+            kobold.faction = Some(Faction::Greenskins);
+            kobold
+        },
+        Monster::GoblinArcher => {
+            let mut goblin = Entity::new(x, y, 'g', DARK_GREEN, "Goblin Archer", true);
+            goblin.fighter = Some(Fighter {base_max_hp: 8, hp: 8, base_defense: 0, base_power: 1, accuracy: 5, evasion: 10, stealth: 0, damage: DamageRange::new(0, 1), xp: 0, xp_reward: 50, damage_type: DamageType::Physical, resistances: Resistances::default(), on_death: DeathCallback::Monster });
+            goblin.ai = Some(Ai::Archer { preferred_range: 4 });
+            goblin.awareness = Some(Awareness::new(10));
+            goblin.faction = Some(Faction::Greenskins);
+            let mut bow = make_item(Item::Bow, x, y);
+            if let Some(equipment) = bow.equipment.as_mut() {
+                equipment.equipped = true;
+            }
+            goblin.inventory.push(bow);
+            let mut arrows = make_item(Item::Arrow, x, y);
+            arrows.quantity = ARCHER_ARROWS;
+            goblin.inventory.push(arrows);
+            goblin
+        },
+        Monster::OrcShaman => {
+            let mut shaman = Entity::new(x, y, 'o', VIOLET, "Orc Shaman", true);
+            shaman.fighter = Some(Fighter {base_max_hp: 12, hp: 12, base_defense: 0, base_power: 2, accuracy: 0, evasion: 5, stealth: 0, damage: DamageRange::new(0, 1), xp: 0, xp_reward: 80, damage_type: DamageType::Physical, resistances: Resistances { fire: 50, ..Default::default() }, on_death: DeathCallback::Monster });
+            shaman.ai = Some(Ai::Caster { spells: vec![
+                SpellSlot::new(MonsterSpell::HealAlly),
+                SpellSlot::new(MonsterSpell::Confuse),
+                SpellSlot::new(MonsterSpell::Fireball),
+            ]});
+            shaman.awareness = Some(Awareness::new(8));
+            shaman.faction = Some(Faction::Greenskins);
+            shaman
+        },
+        Monster::CaveBeetle => {
+            let mut beetle = Entity::new(x, y, 'b', DARKER_AMBER, "Cave Beetle", true);
+            beetle.fighter = Some(Fighter {base_max_hp: 14, hp: 14, base_defense: 2, base_power: 3, accuracy: 0, evasion: 0, stealth: 0, damage: DamageRange::new(0, 2), xp: 0, xp_reward: 40, damage_type: DamageType::Physical, resistances: Resistances { physical: 10, ..Default::default() }, on_death: DeathCallback::Monster });
+            beetle.ai = Some(Ai::Basic);
+            beetle.awareness = Some(Awareness::new(5));
+            beetle.faction = Some(Faction::Wildlife);
+            beetle
+        },
+        Monster::Troll => {
+            let mut troll = Entity::new(x, y, 'T', DARKER_AMBER, "Troll", true);
+            troll.fighter = Some(Fighter {base_max_hp: 30, hp: 30, base_defense: 2, base_power: 4, accuracy: -5, evasion: 0, stealth: 0, damage: DamageRange::new(1, 4), xp: 0, xp_reward: 100, damage_type: DamageType::Physical, resistances: Resistances { fire: -50, ..Default::default() }, on_death: DeathCallback::Monster });
+            troll.ai = Some(Ai::Basic);
+            troll.awareness = Some(Awareness::new(6));
+            troll.faction = Some(Faction::Greenskins);
+            troll
+        },
+        Monster::Skeleton => {
+            let mut skeleton = Entity::new(x, y, 's', LIGHTEST_SEPIA, "Skeleton", true);
+            skeleton.fighter = Some(Fighter {base_max_hp: 25, hp: 25, base_defense: 1, base_power: 6, accuracy: 0, evasion: 5, stealth: 0, damage: DamageRange::new(0, 2), xp: 0, xp_reward: 200, damage_type: DamageType::Physical, resistances: Resistances { lightning: 75, cold: 50, necrotic: 100, ..Default::default() }, on_death: DeathCallback::Monster });
+            skeleton.ai = Some(Ai::Basic);
+            skeleton.awareness = Some(Awareness::new(10));
+            skeleton.faction = Some(Faction::Undead);
+            skeleton
+        },
+        Monster::SkeletonCaptain => {
+            let mut captain = Entity::new(x, y, 'S', LIGHTEST_SEPIA, "Skeleton Captain", true);
+            captain.fighter = Some(Fighter {base_max_hp: 35, hp: 35, base_defense: 2, base_power: 7, accuracy: 5, evasion: 5, stealth: 0, damage: DamageRange::new(1, 3), xp: 0, xp_reward: 300, damage_type: DamageType::Physical, resistances: Resistances { lightning: 75, cold: 50, necrotic: 100, ..Default::default() }, on_death: DeathCallback::Monster });
+            captain.ai = Some(Ai::Basic);
+            captain.awareness = Some(Awareness::new(10));
+            captain.faction = Some(Faction::Undead);
+            captain
+        },
+        Monster::Spectre => {
+            let mut spectre = Entity::new(x, y, 'o', DARKER_AZURE, "Spectre", true);
+            spectre.fighter = Some(Fighter {base_max_hp: 43, hp: 43, base_defense: 4, base_power: 9, accuracy: 10, evasion: 20, stealth: 0, damage: DamageRange::new(1, 3), xp: 0, xp_reward: 250, damage_type: DamageType::Necrotic, resistances: Resistances { physical: 30, fire: -50, necrotic: 100, ..Default::default() }, on_death: DeathCallback::Monster });
+            spectre.ai = Some(Ai::Basic);
+            spectre.awareness = Some(Awareness::new(12));
+            spectre.faction = Some(Faction::Undead);
+            spectre
+        },
+        Monster::Mercenary => {
+            let mut mercenary = Entity::new(x, y, '@', SKY, "Sellsword", true);
+            mercenary.fighter = Some(Fighter {base_max_hp: 25, hp: 25, base_defense: 1, base_power: 4, accuracy: 5, evasion: 5, stealth: 0, damage: DamageRange::new(1, 3), xp: 0, xp_reward: 50, damage_type: DamageType::Physical, resistances: Resistances::default(), on_death: DeathCallback::Monster });
+            mercenary.ai = Some(Ai::Basic);
+            mercenary.awareness = Some(Awareness::new(8));
+            mercenary.faction = Some(Faction::Player);
+            mercenary
+        },
+    }
+}
+
+pub fn make_item(item: Item, x: i32, y: i32) -> Entity {
+    match item {
+        Item::Heal => {
+            let mut object = Entity::new(x, y, '!', VIOLET, "health potion", false);
+            object.item = Some(Item::Heal);
+            object
+        },
+        Item::Lightning => {
+            let mut object = Entity::new(x, y, '#', SCROLL_COLOR, "scroll of lightning bolt", false);
+            object.item = Some(Item::Lightning);
+            object
+        },
+        Item::Fireball => {
+            let mut object = Entity::new(x, y, '#', SCROLL_COLOR, "scroll of firball", false);
+            object.item = Some(Item::Fireball);
+            object
+        },
+        Item::Confuse => {
+            let mut object = Entity::new(x, y, '#', SCROLL_COLOR, "scroll of confusion", false);
+            object.item = Some(Item::Confuse);
+            object
+        },
+        Item::RaiseSkeleton => {
+            let mut object = Entity::new(x, y, '#', SCROLL_COLOR, "scroll of raise skeleton", false);
+            object.item = Some(Item::RaiseSkeleton);
+            object
+        },
+        Item::Tame => {
+            let mut object = Entity::new(x, y, '#', SCROLL_COLOR, "scroll of taming", false);
+            object.item = Some(Item::Tame);
+            object
+        },
+        Item::Identify => {
+            let mut object = Entity::new(x, y, '#', SCROLL_COLOR, "scroll of identify", false);
+            object.item = Some(Item::Identify);
+            object
+        },
+        Item::Recharge => {
+            let mut object = Entity::new(x, y, '#', LIGHT_VIOLET, "scroll of recharging", false);
+            object.item = Some(Item::Recharge);
+            object
+        },
+        Item::Wand { effect, .. } => {
+            let mut object = Entity::new(x, y, '~', LIGHT_VIOLET, &format!("wand of {}", effect), false);
+            object.item = Some(Item::Wand { effect, charges: rand::thread_rng().gen_range(WAND_CHARGES_MIN, WAND_CHARGES_MAX + 1) });
+            object
+        },
+        Item::Ration => {
+            let mut object = Entity::new(x, y, '%', LIGHT_SEPIA, "ration", false);
+            object.item = Some(Item::Ration);
+            object
+        },
+        Item::Corpse { .. } => {
+            let mut object = Entity::new(x, y, '%', DARK_RED, "remains", false);
+            object.item = Some(item);
+            object
+        },
+        Item::Artifact{name: _, value: _} => {
+            let mut object = Entity::new(x, y, '{', GOLD, "artifact", false);
+            object.item = Some(
+                Item::Artifact{
+                    name: namegen::generate_artifact_name(2,7),
+                    value: 250 * rand::thread_rng().gen_range(1, 30)
+                }
+            );
+            object.light = Some(ARTIFACT_LIGHT);
+            object
+        },
+        Item::Gold => {
+            let mut object = Entity::new(x, y, '$', GOLD, "gold coin", false);
+            object.item = Some(Item::Gold);
+            object
+        },
+        Item::Sword => {
+            let mut object = Entity::new(x, y, '/', SKY, "sword", false);
+            object.item = Some(Item::Sword);
+            object.equipment = Some(Equipment{equipped: false, slot: Slot::RightHand, power_bonus: 3, defense_bonus: 0, damage: DamageRange::new(1, 4), max_hp_bonus: 0, resistances: Resistances::default(), ranged: None, noise: 1, rarity: Rarity::Common, on_hit: None});
+            object
+        },
+        Item::Shield => {
+            let mut object = Entity::new(x, y, '[', DARK_ORANGE, "shield", false);
+            object.item = Some(Item::Shield);
+            object.equipment = Some(Equipment{equipped: false, slot: Slot::LeftHand, power_bonus: 0, defense_bonus: 1, damage: DamageRange::default(), max_hp_bonus: 0, resistances: Resistances::default(), ranged: None, noise: 2, rarity: Rarity::Common, on_hit: None});
+            object
+        },
+        Item::Greataxe => {
+            let mut object = Entity::new(x, y, '/', SKY, "greataxe", false);
+            object.item = Some(Item::Greataxe);
+            object.equipment = Some(Equipment{equipped: false, slot: Slot::BothHands, power_bonus: 5, defense_bonus: 0, damage: DamageRange::new(2, 7), max_hp_bonus: 0, resistances: Resistances::default(), ranged: None, noise: 2, rarity: Rarity::Common, on_hit: None});
+            object
+        },
+        Item::Helmet => {
+            let mut object = Entity::new(x, y, '[', SKY, "helmet", false);
+            object.item = Some(Item::Helmet);
+            object.equipment = Some(Equipment{equipped: false, slot: Slot::Head, power_bonus: 0, defense_bonus: 1, damage: DamageRange::default(), max_hp_bonus: 0, resistances: Resistances::default(), ranged: None, noise: 1, rarity: Rarity::Common, on_hit: None});
+            object
+        },
+        Item::LeatherArmor => {
+            let mut object = Entity::new(x, y, '[', DARKER_AMBER, "leather armor", false);
+            object.item = Some(Item::LeatherArmor);
+            object.equipment = Some(Equipment{equipped: false, slot: Slot::Body, power_bonus: 0, defense_bonus: 2, damage: DamageRange::default(), max_hp_bonus: 0, resistances: Resistances::default(), ranged: None, noise: 1, rarity: Rarity::Common, on_hit: None});
+            object
+        },
+        Item::Cloak => {
+            let mut object = Entity::new(x, y, '(', DARK_GREEN, "cloak", false);
+            object.item = Some(Item::Cloak);
+            object.equipment = Some(Equipment{equipped: false, slot: Slot::Back, power_bonus: 0, defense_bonus: 0, damage: DamageRange::default(), max_hp_bonus: 0, resistances: Resistances { cold: 25, ..Default::default() }, ranged: None, noise: 0, rarity: Rarity::Common, on_hit: None});
+            object
+        },
+        Item::Gauntlets => {
+            let mut object = Entity::new(x, y, '[', DARK_ORANGE, "gauntlets", false);
+            object.item = Some(Item::Gauntlets);
+            object.equipment = Some(Equipment{equipped: false, slot: Slot::Hands, power_bonus: 1, defense_bonus: 1, damage: DamageRange::default(), max_hp_bonus: 0, resistances: Resistances::default(), ranged: None, noise: 1, rarity: Rarity::Common, on_hit: None});
+            object
+        },
+        Item::Boots => {
+            let mut object = Entity::new(x, y, '[', DARKER_AMBER, "boots", false);
+            object.item = Some(Item::Boots);
+            object.equipment = Some(Equipment{equipped: false, slot: Slot::Feet, power_bonus: 0, defense_bonus: 1, damage: DamageRange::default(), max_hp_bonus: 0, resistances: Resistances::default(), ranged: None, noise: 1, rarity: Rarity::Common, on_hit: None});
+            object
+        },
+        Item::Ring => {
+            let mut object = Entity::new(x, y, '=', GOLD, "ring of vitality", false);
+            object.item = Some(Item::Ring);
+            object.equipment = Some(Equipment{equipped: false, slot: Slot::LeftFinger, power_bonus: 0, defense_bonus: 0, damage: DamageRange::default(), max_hp_bonus: 10, resistances: Resistances::default(), ranged: None, noise: 0, rarity: Rarity::Common, on_hit: None});
+            object
+        },
+        Item::Amulet => {
+            let mut object = Entity::new(x, y, '"', GOLD, "amulet of warding", false);
+            object.item = Some(Item::Amulet);
+            object.equipment = Some(Equipment{
+                equipped: false, slot: Slot::Neck, power_bonus: 0, defense_bonus: 0, damage: DamageRange::default(), max_hp_bonus: 0,
+                resistances: Resistances { fire: 15, lightning: 15, cold: 15, necrotic: 15, ..Default::default() }, ranged: None, noise: 0, rarity: Rarity::Common, on_hit: None
+            });
+            object
+        },
+        Item::Bow => {
+            let mut object = Entity::new(x, y, ')', DARK_ORANGE, "bow", false);
+            object.item = Some(Item::Bow);
+            object.equipment = Some(Equipment{
+                equipped: false, slot: Slot::BothHands, power_bonus: 0, defense_bonus: 0, damage: DamageRange::default(), max_hp_bonus: 0, resistances: Resistances::default(),
+                ranged: Some(RangedWeapon { range: 8, damage: DamageRange::new(2, 6), ammo: Some(AmmoType::Arrow) }),
+                noise: 0, rarity: Rarity::Common, on_hit: None
+            });
+            object
+        },
+        Item::Crossbow => {
+            let mut object = Entity::new(x, y, '}', SKY, "crossbow", false);
+            object.item = Some(Item::Crossbow);
+            object.equipment = Some(Equipment{
+                equipped: false, slot: Slot::BothHands, power_bonus: 0, defense_bonus: 0, damage: DamageRange::default(), max_hp_bonus: 0, resistances: Resistances::default(),
+                ranged: Some(RangedWeapon { range: 10, damage: DamageRange::new(4, 9), ammo: Some(AmmoType::Bolt) }),
+                noise: 1, rarity: Rarity::Common, on_hit: None
+            });
+            object
+        },
+        Item::ThrowingKnife => {
+            let mut object = Entity::new(x, y, '-', LIGHTEST_SEPIA, "throwing knife", false);
+            object.item = Some(Item::ThrowingKnife);
+            object.equipment = Some(Equipment{
+                equipped: false, slot: Slot::RightHand, power_bonus: 1, defense_bonus: 0, damage: DamageRange::new(0, 1), max_hp_bonus: 0, resistances: Resistances::default(),
+                ranged: Some(RangedWeapon { range: 5, damage: DamageRange::new(2, 5), ammo: None }),
+                noise: 0, rarity: Rarity::Common, on_hit: None
+            });
+            object
+        },
+        Item::Arrow => {
+            let mut object = Entity::new(x, y, '|', LIGHTEST_SEPIA, "arrow", false);
+            object.item = Some(Item::Arrow);
+            object
+        },
+        Item::Bolt => {
+            let mut object = Entity::new(x, y, '|', SKY, "bolt", false);
+            object.item = Some(Item::Bolt);
+            object
+        },
+    }
+}
+
 pub fn from_dungeon_level(table: &[Transition], level: u32) -> u32 {
     table
         .iter()
@@ -0,0 +1,43 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::entities::entity::Entity;
+use crate::entities::entity_actions::DIRECTIONS;
+use crate::map::mapgen::{in_map_bounds, Map};
+
+// how loud different actions are, in tiles the sound carries before fading out
+pub const NOISE_MOVE: i32 = 4;
+pub const NOISE_ATTACK: i32 = 10;
+pub const NOISE_DEATH: i32 = 6;
+
+/// How loud an action by this entity is: its gear makes it louder, and its stealth makes it quieter.
+pub fn action_noise(entity: &Entity, base_noise: i32) -> i32 {
+    (base_noise + entity.equipment_noise() - entity.stealth()).max(0)
+}
+
+/// Spreads a noise out from its origin through open tiles, losing a point of volume per step, so it travels
+/// around corners but not through walls. Returns how loud the noise is on every tile it reached.
+pub fn propagate_noise(map: &Map, origin: (i32, i32), volume: i32) -> HashMap<(i32, i32), i32> {
+    let mut heard = HashMap::new();
+    if volume <= 0 {
+        return heard;
+    }
+    let mut frontier = VecDeque::new();
+    heard.insert(origin, volume);
+    frontier.push_back(origin);
+    while let Some((x, y)) = frontier.pop_front() {
+        let next_volume = heard[&(x, y)] - 1;
+        if next_volume <= 0 {
+            continue;
+        }
+        for &(dx, dy) in DIRECTIONS.iter() {
+            let (next_x, next_y) = (x + dx, y + dy);
+            if in_map_bounds(next_x, next_y)
+                && !map[next_x as usize][next_y as usize].blocked
+                && !heard.contains_key(&(next_x, next_y)) {
+                heard.insert((next_x, next_y), next_volume);
+                frontier.push_back((next_x, next_y));
+            }
+        }
+    }
+    heard
+}
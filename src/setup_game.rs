@@ -7,12 +7,21 @@ use bracket_lib::color::{BLUE, DARK_RED, RED};
 // use tcod::{BackgroundFlag, Console, TextAlignment};
 // use tcod::colors::{DARK_RED, RED, SKY, WHITE};
 
-use crate::{AudioEventProcessor, Camera, Entity, EventBus, EventLogProcessor, GameEngine, GameOccurrenceEventProcessor, initialize_fov, load_configs, make_map, MAP_HEIGHT, MAP_WIDTH, menu, Messages, msgbox, SCREEN_HEIGHT, SCREEN_WIDTH, Tcod};
+use crate::{AudioEventProcessor, Camera, Entity, EventBus, EventLogProcessor, GameEngine, GameOccurrenceEventProcessor, load_configs, make_map, MAP_HEIGHT, MAP_WIDTH, menu, Messages, msgbox, NoiseEventProcessor, SCREEN_HEIGHT, SCREEN_WIDTH, Tcod};
+use crate::entities::combat::DamageRange;
+use crate::entities::damage::{DamageType, Resistances};
+use crate::entities::entity::reserve_ids;
 use crate::entities::equipment::Equipment;
+use crate::entities::faction::Faction;
 use crate::entities::fighter::Fighter;
+use crate::entities::hunger::Hunger;
 use crate::entities::slot::Slot;
-use crate::game_engine::{GameState, PLAYER};
+use crate::game_engine::{GameState, PLAYER, TORCH_COLOR, TORCH_INTENSITY, TORCH_RADIUS};
+use crate::items::affix::Rarity;
+use crate::items::identification::Identification;
 use crate::items::item::Item;
+use crate::map::fov::FovMap;
+use crate::map::lighting::{LightMap, LightSource};
 use crate::util::death_callback::DeathCallback;
 
 pub fn main_menu(tcod: &mut Tcod) {
@@ -30,13 +39,12 @@ pub fn main_menu(tcod: &mut Tcod) {
 
         match choice {
             Some(0) => {
-                let mut game= new_game(tcod);
+                let mut game= new_game();
                 game.run_game_loop(tcod);
             }
             Some(1) => {
                 match load_game() {
                     Ok(mut game) => {
-                        initialize_fov(tcod, &game.map);
                         game.run_game_loop(tcod);
                     },
                     Err(_e) => {
@@ -59,12 +67,13 @@ pub fn load_game() -> Result<GameEngine, Box<dyn Error>> {
     let mut file = File::open("savegame")?;
     file.read_to_string(&mut json_save_state)?;
     let mut result = serde_json::from_str::<GameEngine>(&json_save_state)?;
+    reserve_ids(&result.entities);
     result.set_audio_engine(config);
     result.play_background_music();
     Ok(result)
 }
 
-pub fn new_game(tcod: &mut Tcod) -> GameEngine {
+pub fn new_game() -> GameEngine {
     let config = load_configs();
     let mut player = Entity::new(0, 0, '@', WHITE, "player", true);
     player.alive = true;
@@ -73,9 +82,19 @@ pub fn new_game(tcod: &mut Tcod) -> GameEngine {
         hp: 30,
         base_defense: 2,
         base_power: 3,
+        accuracy: 0,
+        evasion: 5,
+        stealth: 2,
+        damage: DamageRange::new(0, 1),
         xp: 200,
+        xp_reward: 0,
+        damage_type: DamageType::Physical,
+        resistances: Resistances::default(),
         on_death: DeathCallback::Player
     });
+    player.faction = Some(Faction::Player);
+    player.hunger = Some(Hunger::new(config.hunger.max));
+    player.light = Some(LightSource { radius: TORCH_RADIUS, color: TORCH_COLOR, intensity: TORCH_INTENSITY });
 
     let entities = vec![player];
 
@@ -91,7 +110,8 @@ pub fn new_game(tcod: &mut Tcod) -> GameEngine {
         event_processors: vec![
             Box::new(AudioEventProcessor::new()),
             Box::new(GameOccurrenceEventProcessor::new()),
-            Box::new(EventLogProcessor::new())
+            Box::new(EventLogProcessor::new()),
+            Box::new(NoiseEventProcessor::new())
         ],
         entities,
         camera: Camera{
@@ -99,6 +119,13 @@ pub fn new_game(tcod: &mut Tcod) -> GameEngine {
             width: SCREEN_WIDTH, height: SCREEN_HEIGHT,
             map_width: MAP_WIDTH, map_height: MAP_HEIGHT
         },
+        transient_lights: vec![],
+        hazards: vec![],
+        identification: Identification::new(),
+        generated_uniques: vec![],
+        fov: FovMap::new(MAP_WIDTH, MAP_HEIGHT),
+        light_map: LightMap::new(MAP_WIDTH, MAP_HEIGHT),
+        show_ai_debug: false,
         game_state: Box::new(GameState::main())
     };
     let map = make_map(game.borrow_mut(), 1);
@@ -118,12 +145,11 @@ pub fn new_game(tcod: &mut Tcod) -> GameEngine {
     );
     dagger.item = Some(Item::Sword);
     dagger.equipment = Some(Equipment {
-        equipped: true, slot: Slot::LeftHand, max_hp_bonus: 0, defense_bonus: 0, power_bonus: 2
+        equipped: true, slot: Slot::RightHand, max_hp_bonus: 0, defense_bonus: 0, power_bonus: 2, damage: DamageRange::new(0, 2),
+        resistances: Resistances::default(), ranged: None, noise: 0, rarity: Rarity::Common, on_hit: None
     });
     game.entities[PLAYER].inventory.push(dagger);
 
-    initialize_fov(tcod, &game.map);
-
     game.messages.add(
         "Welcome to the Halls of Ruzt - there's no time to change your mind...", RED
     );
@@ -0,0 +1,46 @@
+use crate::entities::combat::{AttackOutcome, AttackRoll, CRITICAL_CHANCE, DamageRange, GLANCING_MARGIN, resolve_attack};
+
+#[test]
+fn rolling_at_or_over_the_hit_chance_misses() {
+    assert_eq!(resolve_attack(70, 70, 10, 2), AttackRoll { outcome: AttackOutcome::Miss, damage: 0 });
+    assert_eq!(resolve_attack(99, 70, 10, 2).outcome, AttackOutcome::Miss);
+}
+
+#[test]
+fn a_solid_hit_deals_damage_minus_defense() {
+    assert_eq!(resolve_attack(30, 70, 10, 2), AttackRoll { outcome: AttackOutcome::Hit, damage: 8 });
+}
+
+#[test]
+fn the_lowest_rolls_are_critical() {
+    assert_eq!(resolve_attack(CRITICAL_CHANCE - 1, 70, 10, 2), AttackRoll { outcome: AttackOutcome::Critical, damage: 18 });
+    assert_eq!(resolve_attack(CRITICAL_CHANCE, 70, 10, 2).outcome, AttackOutcome::Hit);
+}
+
+#[test]
+fn a_critical_always_does_some_damage() {
+    assert_eq!(resolve_attack(0, 70, 1, 50).damage, 1);
+}
+
+#[test]
+fn barely_hitting_is_a_glancing_blow() {
+    assert_eq!(resolve_attack(70 - GLANCING_MARGIN, 70, 10, 2), AttackRoll { outcome: AttackOutcome::Glancing, damage: 4 });
+    assert_eq!(resolve_attack(69, 70, 10, 2).outcome, AttackOutcome::Glancing);
+    assert_eq!(resolve_attack(70 - GLANCING_MARGIN - 1, 70, 10, 2).outcome, AttackOutcome::Hit);
+}
+
+#[test]
+fn a_hit_that_cant_get_through_armour_still_grazes() {
+    assert_eq!(resolve_attack(30, 70, 3, 10), AttackRoll { outcome: AttackOutcome::Glancing, damage: 1 });
+}
+
+#[test]
+fn damage_ranges_roll_within_their_bounds() {
+    let range = DamageRange::new(2, 4);
+    for _ in 0..100 {
+        let damage = range.roll();
+        assert!(2 <= damage && damage <= 4);
+    }
+    assert_eq!(DamageRange::new(3, 1).roll(), 3);
+    assert_eq!(DamageRange::new(1, 2) + DamageRange::new(2, 3), DamageRange::new(3, 5));
+}
@@ -0,0 +1,44 @@
+use crate::entities::damage::{Damage, DamageType, Resistances};
+
+#[test]
+fn no_resistance_takes_the_full_hit() {
+    let resistances = Resistances::default();
+    assert_eq!(resistances.apply(Damage::new(10, DamageType::Fire)), 10);
+}
+
+#[test]
+fn resistance_only_applies_to_its_own_damage_type() {
+    let resistances = Resistances { fire: 50, ..Default::default() };
+    assert_eq!(resistances.apply(Damage::new(10, DamageType::Fire)), 5);
+    assert_eq!(resistances.apply(Damage::new(10, DamageType::Cold)), 10);
+}
+
+#[test]
+fn partial_resistance_rounds_down() {
+    let resistances = Resistances { physical: 25, ..Default::default() };
+    assert_eq!(resistances.apply(Damage::new(7, DamageType::Physical)), 5);
+}
+
+#[test]
+fn vulnerabilities_add_damage() {
+    let resistances = Resistances { necrotic: -50, ..Default::default() };
+    assert_eq!(resistances.apply(Damage::new(10, DamageType::Necrotic)), 15);
+}
+
+#[test]
+fn immunity_is_capped_at_no_damage() {
+    let immune = Resistances { lightning: 100, ..Default::default() };
+    let over_immune = Resistances { lightning: 150, ..Default::default() };
+    assert_eq!(immune.apply(Damage::new(10, DamageType::Lightning)), 0);
+    assert_eq!(over_immune.apply(Damage::new(10, DamageType::Lightning)), 0);
+}
+
+#[test]
+fn resistances_stack() {
+    let armour = Resistances { fire: 30, cold: -20, ..Default::default() };
+    let ring = Resistances { fire: 20, cold: 20, ..Default::default() };
+    let total = armour + ring;
+    assert_eq!(total.get(DamageType::Fire), 50);
+    assert_eq!(total.get(DamageType::Cold), 0);
+    assert_eq!(total.apply(Damage::new(10, DamageType::Fire)), 5);
+}
@@ -0,0 +1,78 @@
+use crate::Messages;
+use crate::entities::entity::Entity;
+use crate::entities::slot::Slot;
+use crate::inventory::inventory_actions::{equip_item, equip_item_in_slot, get_equipped_id_in_slot};
+use crate::items::item::Item;
+use crate::map::mapgen::make_item;
+
+fn is_equipped(item: &Entity) -> bool {
+    item.equipment.map_or(false, |e| e.equipped)
+}
+
+fn slot_of(item: &Entity) -> Slot {
+    item.equipment.unwrap().slot
+}
+
+#[test]
+fn both_hands_overlap_either_hand() {
+    assert!(Slot::BothHands.overlaps(Slot::LeftHand));
+    assert!(Slot::BothHands.overlaps(Slot::RightHand));
+    assert!(Slot::RightHand.overlaps(Slot::BothHands));
+    assert!(!Slot::LeftHand.overlaps(Slot::RightHand));
+    assert!(!Slot::LeftFinger.overlaps(Slot::RightFinger));
+}
+
+#[test]
+fn a_two_handed_weapon_shows_up_in_either_hand() {
+    let mut inventory = vec![make_item(Item::Greataxe, 0, 0)];
+    equip_item(&mut inventory, 0, &mut Messages::new());
+    assert_eq!(get_equipped_id_in_slot(Slot::LeftHand, &inventory), Some(0));
+    assert_eq!(get_equipped_id_in_slot(Slot::RightHand, &inventory), Some(0));
+    assert_eq!(get_equipped_id_in_slot(Slot::Head, &inventory), None);
+}
+
+#[test]
+fn a_two_handed_weapon_takes_off_whatever_is_in_either_hand() {
+    let mut inventory = vec![make_item(Item::Sword, 0, 0), make_item(Item::Shield, 0, 0), make_item(Item::Greataxe, 0, 0)];
+    let mut messages = Messages::new();
+    equip_item(&mut inventory, 0, &mut messages);
+    equip_item(&mut inventory, 1, &mut messages);
+    equip_item(&mut inventory, 2, &mut messages);
+    assert!(!is_equipped(&inventory[0]));
+    assert!(!is_equipped(&inventory[1]));
+    assert!(is_equipped(&inventory[2]));
+}
+
+#[test]
+fn a_one_handed_item_takes_off_a_two_handed_weapon() {
+    let mut inventory = vec![make_item(Item::Greataxe, 0, 0), make_item(Item::Shield, 0, 0)];
+    let mut messages = Messages::new();
+    equip_item(&mut inventory, 0, &mut messages);
+    equip_item(&mut inventory, 1, &mut messages);
+    assert!(!is_equipped(&inventory[0]));
+    assert!(is_equipped(&inventory[1]));
+}
+
+#[test]
+fn a_second_ring_goes_on_the_free_finger() {
+    let mut inventory = vec![make_item(Item::Ring, 0, 0), make_item(Item::Ring, 0, 0)];
+    let mut messages = Messages::new();
+    equip_item(&mut inventory, 0, &mut messages);
+    equip_item(&mut inventory, 1, &mut messages);
+    assert!(is_equipped(&inventory[0]) && is_equipped(&inventory[1]));
+    assert_ne!(slot_of(&inventory[0]), slot_of(&inventory[1]));
+}
+
+#[test]
+fn a_ring_put_on_a_chosen_finger_swaps_out_only_that_ring() {
+    let mut inventory = vec![make_item(Item::Ring, 0, 0), make_item(Item::Ring, 0, 0), make_item(Item::Ring, 0, 0)];
+    let mut messages = Messages::new();
+    equip_item(&mut inventory, 0, &mut messages);
+    equip_item(&mut inventory, 1, &mut messages);
+    let finger = slot_of(&inventory[1]);
+    equip_item_in_slot(&mut inventory, 2, finger, &mut messages);
+    assert!(is_equipped(&inventory[0]));
+    assert!(!is_equipped(&inventory[1]));
+    assert!(is_equipped(&inventory[2]));
+    assert_eq!(slot_of(&inventory[2]), finger);
+}
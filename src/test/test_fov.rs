@@ -0,0 +1,72 @@
+use crate::map::fov::FovMap;
+use crate::map::mapgen::{Map, MAP_HEIGHT, MAP_WIDTH};
+use crate::map::tile::Tile;
+
+fn open_map() -> Map {
+    vec![vec![Tile::ground(); MAP_HEIGHT as usize]; MAP_WIDTH as usize]
+}
+
+#[test]
+fn sees_everything_in_range_of_an_open_room() {
+    let map = open_map();
+    let mut fov = FovMap::new(MAP_WIDTH, MAP_HEIGHT);
+    fov.compute_fov(&map, 40, 30, 5, true);
+    assert!(fov.is_in_fov(40, 30));
+    assert!(fov.is_in_fov(44, 30));
+    assert!(fov.is_in_fov(40, 26));
+    assert!(fov.is_in_fov(37, 33));
+    // the radius is exclusive
+    assert!(!fov.is_in_fov(45, 30));
+    assert!(!fov.is_in_fov(40, 35));
+}
+
+#[test]
+fn no_radius_means_no_limit() {
+    let map = open_map();
+    let mut fov = FovMap::new(MAP_WIDTH, MAP_HEIGHT);
+    fov.compute_fov(&map, 40, 30, 0, true);
+    assert!(fov.is_in_fov(0, 30));
+    assert!(fov.is_in_fov(MAP_WIDTH - 1, MAP_HEIGHT - 1));
+}
+
+#[test]
+fn walls_cast_shadows() {
+    let mut map = open_map();
+    map[42][30] = Tile::wall();
+    let mut fov = FovMap::new(MAP_WIDTH, MAP_HEIGHT);
+    fov.compute_fov(&map, 40, 30, 10, true);
+    assert!(fov.is_in_fov(41, 30));
+    assert!(fov.is_in_fov(42, 30));
+    assert!(!fov.is_in_fov(43, 30));
+    assert!(!fov.is_in_fov(46, 30));
+    // the shadow only covers what's behind the wall
+    assert!(fov.is_in_fov(43, 27));
+    assert!(fov.is_in_fov(38, 30));
+}
+
+#[test]
+fn walls_are_only_seen_when_they_light_up() {
+    let mut map = open_map();
+    map[42][30] = Tile::wall();
+    let mut fov = FovMap::new(MAP_WIDTH, MAP_HEIGHT);
+    fov.compute_fov(&map, 40, 30, 10, false);
+    assert!(fov.is_in_fov(41, 30));
+    assert!(!fov.is_in_fov(42, 30));
+}
+
+#[test]
+fn sees_nothing_from_off_the_map() {
+    let map = open_map();
+    let mut fov = FovMap::new(MAP_WIDTH, MAP_HEIGHT);
+    fov.compute_fov(&map, 40, 30, 5, true);
+    fov.compute_fov(&map, -1, 30, 5, true);
+    assert!(!fov.is_in_fov(40, 30));
+    assert!(!fov.is_in_fov(0, 30));
+}
+
+#[test]
+fn anything_outside_the_fov_map_is_out_of_sight() {
+    let fov = FovMap::new(MAP_WIDTH, MAP_HEIGHT);
+    assert!(!fov.is_in_fov(-1, 0));
+    assert!(!fov.is_in_fov(MAP_WIDTH, 0));
+}
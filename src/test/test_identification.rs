@@ -0,0 +1,82 @@
+use crate::items::identification::Identification;
+use crate::items::item::Item;
+use crate::map::mapgen::make_item;
+
+#[test]
+fn unidentified_items_go_by_their_appearance() {
+    let identification = Identification::new();
+    let potion = make_item(Item::Heal, 0, 0);
+    let scroll = make_item(Item::Identify, 0, 0);
+    assert!(!identification.is_identified(&Item::Heal));
+    assert!(identification.name_of(&potion).ends_with(" potion"));
+    assert_ne!(identification.name_of(&potion), "health potion");
+    assert!(identification.name_of(&scroll).starts_with("scroll labelled "));
+}
+
+#[test]
+fn every_kind_of_scroll_looks_different() {
+    let identification = Identification::new();
+    let names: Vec<String> = [Item::Lightning, Item::Confuse, Item::Fireball, Item::RaiseSkeleton, Item::Tame, Item::Identify]
+        .iter()
+        .map(|item| identification.name_of(&make_item(item.clone(), 0, 0)))
+        .collect();
+    for (i, name) in names.iter().enumerate() {
+        assert!(!names[i + 1..].contains(name));
+    }
+}
+
+#[test]
+fn gear_needs_no_identifying() {
+    let identification = Identification::new();
+    assert!(identification.is_identified(&Item::Sword));
+    assert_eq!(identification.name_of(&make_item(Item::Sword, 0, 0)), "sword");
+}
+
+#[test]
+fn identifying_one_identifies_the_whole_kind() {
+    let mut identification = Identification::new();
+    let potion = make_item(Item::Heal, 0, 0);
+    let appearance = identification.name_of(&potion);
+    assert!(identification.identify(&potion));
+    assert!(!identification.identify(&make_item(Item::Heal, 3, 3)));
+    assert!(identification.is_identified(&Item::Heal));
+    assert_eq!(identification.name_of(&make_item(Item::Heal, 5, 5)), "health potion");
+    assert_eq!(identification.discoveries(), vec![format!("{}: health potion", appearance)]);
+}
+
+#[test]
+fn labels_stick_until_the_kind_is_identified() {
+    let mut identification = Identification::new();
+    let scroll = make_item(Item::Fireball, 0, 0);
+    let appearance = identification.name_of(&scroll);
+    identification.label(&Item::Fireball, "boom");
+    assert_eq!(identification.name_of(&scroll), format!("{} called boom", appearance));
+    identification.label(&Item::Fireball, "");
+    assert_eq!(identification.name_of(&scroll), appearance);
+    identification.label(&Item::Fireball, "boom");
+    identification.identify(&scroll);
+    assert_eq!(identification.name_of(&scroll), "scroll of firball");
+}
+
+#[test]
+fn stacks_are_described_with_their_count() {
+    let mut identification = Identification::new();
+    let mut potions = make_item(Item::Heal, 0, 0);
+    identification.identify(&potions);
+    assert_eq!(identification.describe(&potions), "health potion");
+    potions.quantity = 3;
+    assert_eq!(identification.describe(&potions), "3 health potions");
+}
+
+#[test]
+fn only_the_thing_itself_is_pluralised() {
+    let mut identification = Identification::new();
+    let mut scrolls = make_item(Item::Lightning, 0, 0);
+    scrolls.quantity = 2;
+    let appearance = identification.name_of(&scrolls);
+    assert_eq!(identification.describe(&scrolls), format!("2 {}", appearance.replacen("scroll ", "scrolls ", 1)));
+    identification.label(&Item::Lightning, "zap");
+    assert!(identification.describe(&scrolls).ends_with(" called zap"));
+    identification.identify(&scrolls);
+    assert_eq!(identification.describe(&scrolls), "2 scrolls of lightning bolt");
+}
@@ -0,0 +1,52 @@
+use crate::entities::entity::Entity;
+use crate::inventory::inventory_actions::{add_to_inventory, take_from_stack};
+use crate::items::item::Item;
+use crate::map::mapgen::make_item;
+
+fn arrows(quantity: i32) -> Entity {
+    let mut arrows = make_item(Item::Arrow, 0, 0);
+    arrows.quantity = quantity;
+    arrows
+}
+
+#[test]
+fn matching_items_join_the_existing_stack() {
+    let mut inventory = vec![make_item(Item::Sword, 0, 0), arrows(5)];
+    assert_eq!(add_to_inventory(&mut inventory, arrows(3)), 1);
+    assert_eq!(inventory.len(), 2);
+    assert_eq!(inventory[1].quantity, 8);
+}
+
+#[test]
+fn different_items_get_a_slot_of_their_own() {
+    let mut inventory = vec![arrows(5)];
+    assert_eq!(add_to_inventory(&mut inventory, make_item(Item::Bolt, 0, 0)), 1);
+    assert_eq!(inventory.len(), 2);
+    assert_eq!(inventory[0].quantity, 5);
+}
+
+#[test]
+fn gear_never_stacks() {
+    let mut inventory = vec![make_item(Item::Sword, 0, 0)];
+    assert_eq!(add_to_inventory(&mut inventory, make_item(Item::Sword, 0, 0)), 1);
+    assert_eq!(inventory.len(), 2);
+}
+
+#[test]
+fn taking_part_of_a_stack_leaves_the_rest() {
+    let mut inventory = vec![arrows(5)];
+    let taken = take_from_stack(&mut inventory, 0, 2);
+    assert_eq!(taken.quantity, 2);
+    assert_eq!(taken.item, Some(Item::Arrow));
+    assert_eq!(inventory.len(), 1);
+    assert_eq!(inventory[0].quantity, 3);
+}
+
+#[test]
+fn taking_the_whole_stack_empties_the_slot() {
+    let mut inventory = vec![make_item(Item::Sword, 0, 0), arrows(5)];
+    let taken = take_from_stack(&mut inventory, 1, 5);
+    assert_eq!(taken.quantity, 5);
+    assert_eq!(inventory.len(), 1);
+    assert_eq!(inventory[0].item, Some(Item::Sword));
+}
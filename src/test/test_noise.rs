@@ -0,0 +1,49 @@
+use crate::map::mapgen::{Map, MAP_HEIGHT, MAP_WIDTH};
+use crate::map::noise::propagate_noise;
+use crate::map::tile::Tile;
+
+fn open_map() -> Map {
+    vec![vec![Tile::ground(); MAP_HEIGHT as usize]; MAP_WIDTH as usize]
+}
+
+#[test]
+fn noise_fades_a_point_per_step() {
+    let map = open_map();
+    let heard = propagate_noise(&map, (40, 30), 5);
+    assert_eq!(heard[&(40, 30)], 5);
+    assert_eq!(heard[&(41, 30)], 4);
+    assert_eq!(heard[&(41, 31)], 4);
+    assert_eq!(heard[&(44, 26)], 1);
+    assert!(!heard.contains_key(&(45, 30)));
+}
+
+#[test]
+fn silence_goes_nowhere() {
+    let map = open_map();
+    assert!(propagate_noise(&map, (40, 30), 0).is_empty());
+    assert!(propagate_noise(&map, (40, 30), -3).is_empty());
+}
+
+#[test]
+fn noise_goes_around_walls_rather_than_through_them() {
+    let mut map = open_map();
+    for y in 0..MAP_HEIGHT as usize {
+        map[42][y] = Tile::wall();
+    }
+    map[42][34] = Tile::ground();
+    let heard = propagate_noise(&map, (40, 30), 20);
+    assert!(!heard.contains_key(&(42, 30)));
+    assert_eq!(heard[&(42, 34)], 16);
+    // four steps to the gap, then four back up the other side
+    assert_eq!(heard[&(44, 30)], 12);
+}
+
+#[test]
+fn a_sealed_room_keeps_its_noise_in() {
+    let mut map = open_map();
+    for y in 0..MAP_HEIGHT as usize {
+        map[42][y] = Tile::wall();
+    }
+    let heard = propagate_noise(&map, (40, 30), 30);
+    assert!(heard.keys().all(|&(x, _)| x < 42));
+}
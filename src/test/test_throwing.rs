@@ -0,0 +1,30 @@
+use crate::entities::combat::DamageRange;
+use crate::entities::ranged::{throw_damage, throw_range, THROW_RANGE};
+use crate::items::item::Item;
+use crate::map::mapgen::make_item;
+
+#[test]
+fn light_things_fly_furthest() {
+    assert_eq!(throw_range(&make_item(Item::Fireball, 0, 0)), THROW_RANGE);
+    assert_eq!(throw_range(&make_item(Item::ThrowingKnife, 0, 0)), THROW_RANGE);
+    assert_eq!(throw_range(&make_item(Item::Sword, 0, 0)), THROW_RANGE - 2);
+    assert_eq!(throw_range(&make_item(Item::Greataxe, 0, 0)), THROW_RANGE - 4);
+}
+
+#[test]
+fn throwing_weapons_do_their_ranged_damage() {
+    assert_eq!(throw_damage(&make_item(Item::ThrowingKnife, 0, 0)), DamageRange::new(2, 5));
+}
+
+#[test]
+fn other_weapons_hit_about_as_hard_as_in_the_hand() {
+    // a sword's own 1-4 on top of its +3 power
+    assert_eq!(throw_damage(&make_item(Item::Sword, 0, 0)), DamageRange::new(4, 7));
+}
+
+#[test]
+fn anything_else_hurts_by_its_weight() {
+    assert_eq!(throw_damage(&make_item(Item::Shield, 0, 0)), DamageRange::new(3, 6));
+    assert_eq!(throw_damage(&make_item(Item::Heal, 0, 0)), DamageRange::new(0, 1));
+    assert_eq!(throw_damage(&make_item(Item::Fireball, 0, 0)), DamageRange::new(0, 0));
+}
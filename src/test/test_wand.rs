@@ -0,0 +1,63 @@
+use crate::entities::damage::DamageType;
+use crate::items::identification::Identification;
+use crate::items::item::Item;
+use crate::items::shop::price;
+use crate::items::wand::*;
+use crate::map::mapgen::make_item;
+
+fn wand(charges: i32) -> Item {
+    Item::Wand { effect: WandEffect::Lightning, charges }
+}
+
+#[test]
+fn zapping_spends_a_charge() {
+    let mut item = wand(3);
+    assert_eq!(spend_charge(&mut item), 2);
+    assert_eq!(item, wand(2));
+    assert_eq!(spend_charge(&mut item), 1);
+    assert_eq!(spend_charge(&mut item), 0);
+}
+
+#[test]
+fn only_wands_have_charges() {
+    let mut item = Item::Lightning;
+    assert_eq!(spend_charge(&mut item), 0);
+    add_charges(&mut item, 3);
+    assert_eq!(item, Item::Lightning);
+}
+
+#[test]
+fn recharging_adds_to_what_is_left() {
+    let mut item = wand(2);
+    add_charges(&mut item, RECHARGE_MIN);
+    assert_eq!(item, wand(2 + RECHARGE_MIN));
+}
+
+#[test]
+fn a_fuller_wand_is_likelier_to_explode() {
+    assert_eq!(recharge_explode_chance(0), RECHARGE_EXPLODE_CHANCE);
+    assert_eq!(recharge_explode_chance(4), RECHARGE_EXPLODE_CHANCE + 4 * RECHARGE_EXPLODE_CHANCE_PER_CHARGE);
+    assert!(recharge_explode_chance(WAND_CHARGES_MAX + RECHARGE_MAX) < 100);
+}
+
+#[test]
+fn a_wand_explodes_harder_the_more_charges_it_holds() {
+    let empty = explosion_damage(WandEffect::Fireball, 0);
+    let full = explosion_damage(WandEffect::Fireball, 5);
+    assert_eq!(empty.amount, WAND_EXPLOSION_DAMAGE);
+    assert_eq!(full.amount, WAND_EXPLOSION_DAMAGE + 5 * WAND_EXPLOSION_DAMAGE_PER_CHARGE);
+    assert_eq!(full.damage_type, DamageType::Fire);
+    assert_eq!(explosion_damage(WandEffect::Lightning, 1).damage_type, DamageType::Lightning);
+}
+
+#[test]
+fn an_unidentified_wand_is_priced_without_its_charges() {
+    let mut identification = Identification::new();
+    let mut few = make_item(wand(0), 0, 0);
+    let mut many = make_item(wand(0), 0, 0);
+    few.item = Some(wand(1));
+    many.item = Some(wand(7));
+    assert_eq!(price(&few, &identification), price(&many, &identification));
+    identification.identify(&few);
+    assert!(price(&few, &identification) < price(&many, &identification));
+}
@@ -1,74 +1,649 @@
 use std::borrow::BorrowMut;
+use std::collections::HashMap;
 
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use tcod::colors::RED;
+use tcod::colors::{LIGHT_RED, LIGHT_VIOLET, LIGHTEST_YELLOW, ORANGE, VIOLET};
 
+use crate::entities::awareness::{AiState, Awareness, DEFAULT_SIGHT_RADIUS, WAKE_RADIUS};
+use crate::entities::boss::{Boss, BossAbility};
+use crate::entities::companion::{COMPANION_LEASH, CompanionOrder};
 use crate::entities::entity::Entity;
-use crate::entities::entity_actions::move_towards;
-use crate::events::game_event_processing::{EventType, GameEvent};
-use crate::framework::Tcod;
-use crate::game_engine::{GameEngine, PLAYER};
-use crate::map::mapgen::{Map, MAP_HEIGHT, MAP_WIDTH};
+use crate::entities::entity_actions::{DIRECTIONS, move_away_from, move_by, move_towards, random_direction};
+use crate::entities::faction::Relationship;
+use crate::entities::group::{GroupMembership, GroupRole};
+use crate::entities::ranged::{find_ammo, fire_projectile, get_ranged_weapon};
+use crate::entities::status_effect::{StatusEffect, StatusEffectKind};
+use crate::events::game_event_processing::{EventData, EventType, GameEvent};
+use crate::game_engine::{FOV_LIGHT_WALLS, GameEngine, PLAYER};
+use crate::inventory::inventory_actions::explode_fireball;
+use crate::items::item::FIREBALL_RADIUS;
+use crate::map::fov::FovMap;
+use crate::map::hazard::{Hazard, HazardKind};
+use crate::map::map_functions::is_blocked;
+use crate::map::mapgen::{in_map_bounds, make_monster, Map, MAP_HEIGHT, MAP_WIDTH};
+use crate::util::line::line;
 use crate::util::mut_two::mut_two;
 
+// how far a monster can reach with its spells
+pub const MONSTER_SPELL_RANGE: i32 = 7;
+pub const MONSTER_HEAL_AMOUNT: i32 = 8;
+pub const MONSTER_CONFUSE_TURNS: i32 = 4;
+// chance in percent that a wandering monster takes a step on its turn
+pub const WANDER_MOVE_CHANCE: i32 = 50;
+// how far a pack member strays from its leader, and how long it panics once the leader is dead
+pub const FOLLOW_DISTANCE: i32 = 2;
+pub const SCATTER_TURNS: i32 = 10;
+// turns of warning the player gets before a boss's attacks land, and how long an eruption keeps burning
+pub const SLAM_WARNING_TURNS: i32 = 1;
+pub const ERUPTION_WARNING_TURNS: i32 = 2;
+pub const ERUPTION_BURN_TURNS: i32 = 3;
+// how far from the target an eruption's flames can spread
+pub const ERUPTION_SPREAD: i32 = 2;
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Ai {
     Basic,
-    Confused {                  // enum values can hold data. Dope
-    previous_ai: Box<Ai>,
-        num_turns: i32
-    },
+    /// Keeps its distance and shoots while it has something to fire, then falls back to melee.
+    Archer { preferred_range: i32 },
+    /// Casts whichever of its spells are ready and useful, and otherwise fights like a basic monster.
+    Caster { spells: Vec<SpellSlot> },
+    /// Fights like a basic monster until its hp drops to the given percentage, then runs for it.
+    Coward { flee_below_percent: i32 },
+    /// Works through the boss's phases as it gets hurt, using each phase's abilities as their cooldowns allow.
+    Boss { boss: Boss, phase: usize, cooldowns: Vec<i32> },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MonsterSpell {
+    HealAlly,
+    Confuse,
+    Fireball,
+}
+
+impl MonsterSpell {
+    // turns a caster has to wait before it can cast the same spell again
+    pub fn cooldown(self) -> i32 {
+        match self {
+            MonsterSpell::HealAlly => 5,
+            MonsterSpell::Confuse => 8,
+            MonsterSpell::Fireball => 10,
+        }
+    }
+}
+
+impl std::fmt::Display for MonsterSpell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MonsterSpell::HealAlly => "heal ally",
+            MonsterSpell::Confuse => "confuse",
+            MonsterSpell::Fireball => "fireball",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A spell a caster knows, along with how many turns are left until it can be cast again.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpellSlot {
+    pub spell: MonsterSpell,
+    pub cooldown: i32,
+}
+
+impl SpellSlot {
+    pub fn new(spell: MonsterSpell) -> Self {
+        SpellSlot { spell, cooldown: 0 }
+    }
 }
 
-pub fn ai_take_turn(monster_id: usize, tcod: &Tcod, game: &mut GameEngine) {
+pub fn ai_take_turn(monster_id: usize, game: &mut GameEngine) {
     use Ai::*;
+    // confusion overrides whatever the monster would normally do
+    if game.entities[monster_id].has_status_effect(StatusEffectKind::Confused) {
+        ai_confused(monster_id, game);
+        return;
+    }
+    // a shopkeeper minds the shop until somebody gives them a reason not to
+    let monster = &game.entities[monster_id];
+    if monster.shop.is_some() && monster.awareness.map_or(true, |a| a.provoked_by.is_none()) {
+        return;
+    }
+    check_leader(monster_id, game);
     if let Some(ai) = game.entities[monster_id].ai.take() {               // take() removes to the option from Option - it then becomes empty
-        let new_ai = match ai {
-            Basic => ai_basic(monster_id, tcod, game),
-            Confused {
-                previous_ai,
-                num_turns
-            } => ai_confused(monster_id, tcod, game, previous_ai, num_turns)
+        let (state, target_id) = update_ai_state(monster_id, &ai, game);
+        if let Some(companion) = game.entities[monster_id].companion {
+            if obey_orders(monster_id, companion.order, state, target_id, game) {
+                game.entities[monster_id].ai = Some(ai);
+                return;
+            }
+        }
+        let new_ai = match (state, target_id) {
+            (AiState::Wandering, _) => {
+                if !follow_leader(monster_id, game) {
+                    wander(monster_id, game);
+                }
+                ai
+            }
+            (AiState::Searching, _) => {
+                search_for_target(monster_id, game);
+                ai
+            }
+            (AiState::Fleeing, Some(target_id)) => {
+                flee(monster_id, target_id, game);
+                ai
+            }
+            (AiState::Hunting, Some(target_id)) => match ai {
+                Basic => ai_basic(monster_id, target_id, game),
+                Archer { preferred_range } => ai_archer(monster_id, target_id, preferred_range, game),
+                Caster { spells } => ai_caster(monster_id, target_id, spells, game),
+                Coward { flee_below_percent } => ai_coward(monster_id, target_id, flee_below_percent, game),
+                Ai::Boss { boss, phase, cooldowns } => ai_boss(monster_id, target_id, boss, phase, cooldowns, game),
+            },
+            // asleep, or just woken up with nothing in sight
+            _ => ai,
         };
         game.entities[monster_id].ai = Some(new_ai);                      // the AI is then put back here
     }
 }
 
-fn ai_basic(monster_id: usize, tcod: &Tcod, game: &mut GameEngine) -> Ai {
-    // a basic ai takes a turn. If you can see it, it can see you
+impl Ai {
+    // how low the monster's hp has to drop, in percent, before it runs away
+    pub fn flee_below_percent(&self) -> i32 {
+        match self {
+            Ai::Coward { flee_below_percent } => *flee_below_percent,
+            _ => 0,
+        }
+    }
+}
+
+/// Moves the monster between states based on what it can perceive and how badly it's hurt,
+/// and returns the state it should act on this turn along with the hostile it's reacting to, if it sees one.
+fn update_ai_state(monster_id: usize, ai: &Ai, game: &mut GameEngine) -> (AiState, Option<usize>) {
+    use AiState::*;
+    let target_id = find_target(monster_id, game);
+    let sees_target = target_id.is_some();
+    let monster = &game.entities[monster_id];
+    let max_hp = monster.max_hp().max(1);
+    let hp = monster.fighter.map_or(max_hp, |f| f.hp);
+    let frightened = monster.has_status_effect(StatusEffectKind::Frightened);
+    let mut awareness = monster.awareness.unwrap_or_else(|| Awareness::new(DEFAULT_SIGHT_RADIUS));
+    let wake_radius = (WAKE_RADIUS - game.entities[PLAYER].stealth()).max(1);
+    let near_player = monster.distance_to(&game.entities[PLAYER]) <= wake_radius as f32;
+
+    let state = match awareness.state {
+        // a sleeper only wakes to noise, to the player creeping right up to it, or to getting hurt
+        Asleep if near_player || hp < max_hp => Hunting,
+        Asleep => Asleep,
+        _ if sees_target && (frightened || hp * 100 / max_hp <= ai.flee_below_percent()) => Fleeing,
+        _ if sees_target => Hunting,
+        Fleeing => {
+            // out of sight, out of mind
+            awareness.forget_target();
+            Wandering
+        }
+        Hunting | Searching if awareness.last_known_target_pos.is_some() => Searching,
+        _ => Wandering,
+    };
+    if let Some(target_id) = target_id.filter(|_| state != Asleep) {
+        let target_pos = game.entities[target_id].pos();
+        awareness.spot_target(target_pos);
+        if let Some(group) = game.entities[monster_id].group {
+            alert_pack(group.group_id, target_pos, &mut game.entities);
+        }
+    }
+    if state != Wandering {
+        awareness.wander_target = None;
+    }
+    awareness.state = state;
+    game.entities[monster_id].awareness = Some(awareness);
+    (state, target_id)
+}
+
+// computes the monster's own field of view, only over the part of the map within its sight radius
+fn monster_fov(monster_id: usize, game: &GameEngine) -> FovMap {
+    let monster = &game.entities[monster_id];
+    let sight_radius = monster.awareness.map_or(DEFAULT_SIGHT_RADIUS, |a| a.sight_radius);
+    let mut fov = FovMap::around(monster.x, monster.y, sight_radius);
+    fov.compute_fov(&game.map, monster.x, monster.y, sight_radius, FOV_LIGHT_WALLS);
+    fov
+}
+
+// the closest thing in the monster's sight that it's hostile to, be it the player or another monster
+fn find_target(monster_id: usize, game: &GameEngine) -> Option<usize> {
+    let monster = &game.entities[monster_id];
+    let sight_radius = monster.awareness.map_or(DEFAULT_SIGHT_RADIUS, |a| a.sight_radius);
+    let candidates: Vec<usize> = game.entities.iter().enumerate()
+        .filter(|&(id, e)| id != monster_id && e.alive && e.fighter.is_some() && monster.is_hostile_to(e))
+        .filter(|(_, e)| monster.distance_to(e) <= sight_radius as f32)
+        .map(|(id, _)| id)
+        .collect();
+    if candidates.is_empty() {
+        // nothing worth the cost of a field of view
+        return None;
+    }
+    let fov = monster_fov(monster_id, game);
+    candidates.into_iter()
+        .filter(|&id| fov.is_in_fov(game.entities[id].x, game.entities[id].y))
+        .min_by(|&a, &b| monster.distance_to(&game.entities[a]).partial_cmp(&monster.distance_to(&game.entities[b])).unwrap())
+}
+
+fn ai_basic(monster_id: usize, target_id: usize, game: &mut GameEngine) -> Ai {
+    // a basic ai just goes straight for its target
+    chase_and_attack(monster_id, target_id, game);
+    Ai::Basic
+}
+
+fn chase_and_attack(monster_id: usize, target_id: usize, game: &mut GameEngine) {
     let entities: &mut Vec<Entity> = game.entities.borrow_mut();
     let event_bus = game.event_bus.borrow_mut();
-    let (monster_x, monster_y) = entities[monster_id].pos();
-    if tcod.fov.is_in_fov(monster_x, monster_y) {
-        if entities[monster_id].distance_to(&entities[PLAYER]) >= 2.0 {
-            // move towards player if far away
-            let (player_x, player_y) = entities[PLAYER].pos();
-            move_towards(monster_id, player_x, player_y, &game.map, entities);
-            event_bus.add_event(GameEvent::from_type(EventType::MonsterMove));
-
-        } else {
-            // close enough to start a war
-            let (monster, player) = mut_two(monster_id, PLAYER, entities);
-            monster.attack(player, event_bus);
-            event_bus.add_event(GameEvent::from_type(EventType::MonsterAttack));
+    let (target_x, target_y) = entities[target_id].pos();
+    if entities[monster_id].distance_to(&entities[target_id]) >= 2.0 {
+        // move towards the target if far away; a pack spreads out to come at it from every side
+        let (dest_x, dest_y) = match entities[monster_id].group {
+            Some(_) => flanking_tile(monster_id, target_id, &game.map, entities).unwrap_or((target_x, target_y)),
+            None => (target_x, target_y),
+        };
+        move_towards(monster_id, dest_x, dest_y, &game.map, entities);
+        event_bus.add_event(GameEvent::from_type(EventType::MonsterMove));
+
+    } else {
+        // close enough to start a war
+        let (monster, target) = mut_two(monster_id, target_id, entities);
+        monster.attack(target, event_bus);
+        event_bus.add_event(GameEvent::from_type(EventType::MonsterAttack));
+
+    }
+}
+
+// the free tile next to the target that's closest to the monster, so the pack fills in around it
+fn flanking_tile(monster_id: usize, target_id: usize, map: &Map, entities: &[Entity]) -> Option<(i32, i32)> {
+    let (target_x, target_y) = entities[target_id].pos();
+    let monster = &entities[monster_id];
+    DIRECTIONS.iter()
+        .map(|&(dx, dy)| (target_x + dx, target_y + dy))
+        .filter(|&(x, y)| !is_blocked(x, y, map, entities))
+        .min_by(|a, b| monster.distance(a.0, a.1).partial_cmp(&monster.distance(b.0, b.1)).unwrap())
+}
+
+fn find_leader(group_id: u32, entities: &[Entity]) -> Option<usize> {
+    entities.iter().position(|e| e.alive && e.group == Some(GroupMembership { group_id, role: GroupRole::Leader }))
+}
 
+// once one of the pack has spotted a target, the rest of it comes looking
+fn alert_pack(group_id: u32, target_pos: (i32, i32), entities: &mut [Entity]) {
+    for entity in entities.iter_mut().filter(|e| e.alive && e.group.map_or(false, |g| g.group_id == group_id)) {
+        if let Some(awareness) = entity.awareness.as_mut() {
+            awareness.alert(target_pos);
         }
     }
-    Ai::Basic
 }
 
-fn ai_confused(monster_id:usize, _tcod: & Tcod, game: &mut GameEngine, previous_ai: Box<Ai>, num_turns: i32) -> Ai {
-    let x = rand::thread_rng().gen_range(0, MAP_WIDTH);
-    let y = rand::thread_rng().gen_range(0, MAP_HEIGHT);
-    let messages = game.messages.borrow_mut();
+// a pack member whose leader has fallen loses its nerve and scatters
+fn check_leader(monster_id: usize, game: &mut GameEngine) {
+    let group = match game.entities[monster_id].group {
+        Some(group) if group.role == GroupRole::Member => group,
+        _ => return,
+    };
+    if find_leader(group.group_id, &game.entities).is_some() {
+        return;
+    }
+    let monster = &mut game.entities[monster_id];
+    monster.group = None;
+    monster.add_status_effect(StatusEffect::new(StatusEffectKind::Frightened, SCATTER_TURNS, 0));
+    let (x, y) = monster.pos();
+    if game.is_visible(x, y) {
+        game.messages.add(format!("With its leader gone, the {} panics!", game.entities[monster_id].name), LIGHTEST_YELLOW);
+    }
+}
+
+// a pack member with nothing better to do keeps close to its leader; returns false if it has no leader to follow
+fn follow_leader(monster_id: usize, game: &mut GameEngine) -> bool {
+    let leader_id = match game.entities[monster_id].group {
+        Some(group) if group.role == GroupRole::Member => find_leader(group.group_id, &game.entities),
+        _ => None,
+    };
+    match leader_id {
+        Some(leader_id) => {
+            keep_close(monster_id, leader_id, game);
+            true
+        }
+        None => false,
+    }
+}
+
+fn keep_close(monster_id: usize, leader_id: usize, game: &mut GameEngine) {
+    let (leader_x, leader_y) = game.entities[leader_id].pos();
+    if game.entities[monster_id].distance(leader_x, leader_y) > FOLLOW_DISTANCE as f32 {
+        let map: &Map = &game.map;
+        move_towards(monster_id, leader_x, leader_y, map, &mut game.entities);
+        game.add_event(GameEvent::from_type(EventType::MonsterMove));
+    }
+}
+
+/// Lets a companion's orders decide its turn. Returns false if it should fight like any other monster instead.
+fn obey_orders(monster_id: usize, order: CompanionOrder, state: AiState, target_id: Option<usize>, game: &mut GameEngine) -> bool {
+    use AiState::*;
+    let within_reach = target_id.map_or(false, |id| game.entities[monster_id].distance_to(&game.entities[id]) < 2.0);
+    let near_player = target_id.map_or(false, |id| game.entities[id].distance_to(&game.entities[PLAYER]) <= COMPANION_LEASH as f32);
+    match (order, state) {
+        // running for its life trumps any order
+        (_, Fleeing) => false,
+        (CompanionOrder::Stay, Hunting) => !within_reach,
+        (CompanionOrder::Stay, _) => true,
+        (CompanionOrder::Follow, Hunting) if near_player => false,
+        (CompanionOrder::Attack, Hunting) | (CompanionOrder::Attack, Searching) => false,
+        _ => {
+            keep_close(monster_id, PLAYER, game);
+            true
+        }
+    }
+}
+
+fn search_for_target(monster_id: usize, game: &mut GameEngine) {
+    let entities: &mut Vec<Entity> = game.entities.borrow_mut();
+    let event_bus = game.event_bus.borrow_mut();
+    if let Some(mut awareness) = entities[monster_id].awareness {
+        if let Some((last_x, last_y)) = awareness.last_known_target_pos {
+            if awareness.search_turns <= 0 {
+                awareness.forget_target();
+            } else {
+                if entities[monster_id].pos() == (last_x, last_y) {
+                    // the trail has gone cold, so poke around the area
+                    let dx = rand::thread_rng().gen_range(-1, 2);
+                    let dy = rand::thread_rng().gen_range(-1, 2);
+                    move_by(monster_id, dx, dy, &game.map, entities);
+                } else {
+                    move_towards(monster_id, last_x, last_y, &game.map, entities);
+                }
+                awareness.search_turns -= 1;
+                event_bus.add_event(GameEvent::from_type(EventType::MonsterMove));
+            }
+            entities[monster_id].awareness = Some(awareness);
+        }
+    }
+}
+
+fn ai_archer(monster_id: usize, target_id: usize, preferred_range: i32, game: &mut GameEngine) -> Ai {
+    let monster = &game.entities[monster_id];
+    let weapon = get_ranged_weapon(monster)
+        .filter(|&(weapon_id, weapon)| find_ammo(monster, weapon_id, weapon).is_some())
+        .map(|(_, weapon)| weapon);
+    let target_pos = game.entities[target_id].pos();
+    let distance = monster.distance_to(&game.entities[target_id]);
+    match weapon {
+        Some(weapon) => {
+            let map: &Map = &game.map;
+            if distance < preferred_range as f32
+                && move_away_from(monster_id, target_pos.0, target_pos.1, map, &mut game.entities) {
+                // too close for comfort, so back off before shooting
+                game.add_event(GameEvent::from_type(EventType::MonsterMove));
+            } else if distance >= 2.0 && distance <= weapon.range as f32 && has_clear_shot(monster_id, target_pos, game) {
+                fire_projectile(monster_id, target_pos, game);
+            } else {
+                chase_and_attack(monster_id, target_id, game);
+            }
+        }
+        // out of arrows, so it's down to fists
+        None => chase_and_attack(monster_id, target_id, game)
+    }
+    Ai::Archer { preferred_range }
+}
+
+// checks that nothing stands between the monster and its target
+fn has_clear_shot(monster_id: usize, target: (i32, i32), game: &GameEngine) -> bool {
+    line(game.entities[monster_id].pos(), target).into_iter()
+        .take_while(|&pos| pos != target)
+        .all(|(x, y)| !is_blocked(x, y, &game.map, &game.entities))
+}
+
+fn ai_caster(monster_id: usize, target_id: usize, mut spells: Vec<SpellSlot>, game: &mut GameEngine) -> Ai {
+    for slot in spells.iter_mut() {
+        if slot.cooldown > 0 {
+            slot.cooldown -= 1;
+        }
+    }
+    let cast = spells.iter()
+        .position(|slot| slot.cooldown == 0 && cast_monster_spell(monster_id, target_id, slot.spell, game));
+    match cast {
+        Some(slot_id) => spells[slot_id].cooldown = spells[slot_id].spell.cooldown(),
+        None => chase_and_attack(monster_id, target_id, game),
+    }
+    Ai::Caster { spells }
+}
+
+// casts the spell if there's a good reason to, returning whether it was cast
+fn cast_monster_spell(monster_id: usize, target_id: usize, spell: MonsterSpell, game: &mut GameEngine) -> bool {
+    let caster_name = game.entities[monster_id].name.clone();
+    let target_distance = game.entities[monster_id].distance_to(&game.entities[target_id]);
+    let target_pos = game.entities[target_id].pos();
+    let cast = match spell {
+        MonsterSpell::HealAlly => {
+            let fov = monster_fov(monster_id, game);
+            let ally_id = most_wounded_ally(monster_id, &fov, &game.entities);
+            if let Some(ally_id) = ally_id {
+                game.entities[ally_id].heal(MONSTER_HEAL_AMOUNT);
+                let (ally_x, ally_y) = game.entities[ally_id].pos();
+                if game.is_visible(ally_x, ally_y) {
+                    game.messages.add(format!("The {} mends the wounds of the {}.", caster_name, game.entities[ally_id].name), LIGHT_VIOLET);
+                }
+            }
+            ally_id.is_some()
+        }
+        MonsterSpell::Confuse => {
+            let usable = target_distance <= MONSTER_SPELL_RANGE as f32
+                && !game.entities[target_id].has_status_effect(StatusEffectKind::Confused);
+            if usable {
+                game.entities[target_id].add_status_effect(StatusEffect::new(StatusEffectKind::Confused, MONSTER_CONFUSE_TURNS, 0));
+                if target_id == PLAYER {
+                    game.messages.add(format!("The {} chants, and your head starts to spin!", caster_name), VIOLET);
+                } else if game.is_visible(target_pos.0, target_pos.1) {
+                    game.messages.add(format!("The {} chants, and the {} starts to stumble around.", caster_name, game.entities[target_id].name), VIOLET);
+                }
+            }
+            usable
+        }
+        MonsterSpell::Fireball => {
+            // no caster is mad enough to catch itself or its friends in the blast
+            let caster = &game.entities[monster_id];
+            let friends_in_blast = game.entities.iter().enumerate()
+                .filter(|(_, e)| e.alive && e.fighter.is_some() && e.distance(target_pos.0, target_pos.1) <= FIREBALL_RADIUS as f32)
+                .any(|(id, e)| id == monster_id || caster.relationship_to(e) == Relationship::Allied);
+            let usable = !friends_in_blast && target_distance <= MONSTER_SPELL_RANGE as f32;
+            if usable {
+                game.messages.add(format!("The {} hurls a fireball!", caster_name), ORANGE);
+                explode_fireball(monster_id, target_pos.0, target_pos.1, game);
+            }
+            usable
+        }
+    };
+    if cast {
+        game.add_event(GameEvent::from_type_with_data(
+            EventType::MonsterCastSpell,
+            HashMap::from([
+                ("caster_name".to_string(), EventData::String(caster_name)),
+                ("spell".to_string(), EventData::String(spell.to_string())),
+            ])
+        ));
+    }
+    cast
+}
+
+// the ally in sight and in range that has lost the largest share of its hp
+fn most_wounded_ally(monster_id: usize, fov: &FovMap, entities: &[Entity]) -> Option<usize> {
+    let caster = &entities[monster_id];
+    entities.iter().enumerate()
+        .filter(|&(id, e)| id != monster_id && e.alive && caster.relationship_to(e) == Relationship::Allied)
+        .filter(|(_, e)| fov.is_in_fov(e.x, e.y) && caster.distance_to(e) <= MONSTER_SPELL_RANGE as f32)
+        .filter_map(|(id, e)| e.fighter.map(|f| (id, f.hp as f32 / e.max_hp().max(1) as f32)))
+        .filter(|&(_, health)| health < 1.0)
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .map(|(id, _)| id)
+}
+
+fn ai_coward(monster_id: usize, target_id: usize, flee_below_percent: i32, game: &mut GameEngine) -> Ai {
+    // a coward fights like anything else until it's hurt enough to flee
+    chase_and_attack(monster_id, target_id, game);
+    Ai::Coward { flee_below_percent }
+}
+
+fn ai_boss(monster_id: usize, target_id: usize, boss: Boss, mut phase: usize, mut cooldowns: Vec<i32>, game: &mut GameEngine) -> Ai {
+    let template = boss.template();
+    let monster = &game.entities[monster_id];
+    let hp = monster.fighter.map_or(0, |f| f.hp);
+    let reached = template.phase_at(hp * 100 / monster.max_hp().max(1));
+    if reached > phase {
+        // a new phase comes with a fresh set of abilities, all ready to go
+        phase = reached;
+        cooldowns = vec![0; template.phases[phase].abilities.len()];
+        if let Some(announcement) = template.phases[phase].announcement {
+            game.messages.add(announcement, LIGHT_RED);
+        }
+    }
+    for cooldown in cooldowns.iter_mut() {
+        if *cooldown > 0 {
+            *cooldown -= 1;
+        }
+    }
+    let abilities = template.phases[phase].abilities;
+    let used = abilities.iter().enumerate()
+        .position(|(slot_id, slot)| cooldowns[slot_id] == 0 && use_boss_ability(monster_id, target_id, slot.ability, game));
+    match used {
+        Some(slot_id) => cooldowns[slot_id] = abilities[slot_id].cooldown,
+        None => chase_and_attack(monster_id, target_id, game),
+    }
+    Ai::Boss { boss, phase, cooldowns }
+}
+
+// uses the ability if there's a good reason to, returning whether it was used
+fn use_boss_ability(monster_id: usize, target_id: usize, ability: BossAbility, game: &mut GameEngine) -> bool {
+    let boss_name = game.entities[monster_id].name.clone();
+    let (boss_x, boss_y) = game.entities[monster_id].pos();
+    let (target_x, target_y) = game.entities[target_id].pos();
+    let used = match ability {
+        BossAbility::Slam { radius, damage } => {
+            let usable = (target_x - boss_x).abs() <= radius && (target_y - boss_y).abs() <= radius;
+            if usable {
+                for x in (boss_x - radius)..=(boss_x + radius) {
+                    for y in (boss_y - radius)..=(boss_y + radius) {
+                        if (x, y) != (boss_x, boss_y) && in_map_bounds(x, y) && !game.map[x as usize][y as usize].blocked {
+                            game.hazards.push(Hazard::new(x, y, HazardKind::Shockwave, damage, SLAM_WARNING_TURNS, 1));
+                        }
+                    }
+                }
+                game.messages.add(format!("The {} raises its weapon high, ready to smash the ground around it!", boss_name), LIGHT_RED);
+            }
+            usable
+        }
+        BossAbility::Summon { monster, count, max_minions } => {
+            let group_id = game.entities[monster_id].group.map(|g| g.group_id);
+            let minions = game.entities.iter()
+                .filter(|e| e.alive && e.group.map_or(false, |g| Some(g.group_id) == group_id && g.role == GroupRole::Member))
+                .count() as i32;
+            let spots: Vec<(i32, i32)> = DIRECTIONS.iter()
+                .map(|&(dx, dy)| (boss_x + dx, boss_y + dy))
+                .filter(|&(x, y)| !is_blocked(x, y, &game.map, &game.entities))
+                .take(count.min(max_minions - minions).max(0) as usize)
+                .collect();
+            let usable = !spots.is_empty();
+            for (x, y) in spots {
+                let mut minion = make_monster(monster, x, y);
+                minion.group = group_id.map(|group_id| GroupMembership { group_id, role: GroupRole::Member });
+                if let Some(awareness) = minion.awareness.as_mut() {
+                    awareness.alert((target_x, target_y));
+                }
+                minion.alive = true;
+                game.entities.push(minion);
+            }
+            if usable {
+                game.messages.add(format!("The {} calls for aid!", boss_name), LIGHT_RED);
+            }
+            usable
+        }
+        BossAbility::Eruption { tiles, damage } => {
+            let usable = game.entities[monster_id].distance(target_x, target_y) <= MONSTER_SPELL_RANGE as f32;
+            if usable {
+                // the target's own tile always goes up, and the rest land wherever they like nearby
+                game.hazards.push(Hazard::new(target_x, target_y, HazardKind::Hellfire, damage, ERUPTION_WARNING_TURNS, ERUPTION_BURN_TURNS));
+                for _ in 1..tiles {
+                    let x = target_x + rand::thread_rng().gen_range(-ERUPTION_SPREAD, ERUPTION_SPREAD + 1);
+                    let y = target_y + rand::thread_rng().gen_range(-ERUPTION_SPREAD, ERUPTION_SPREAD + 1);
+                    if in_map_bounds(x, y) && !game.map[x as usize][y as usize].blocked {
+                        game.hazards.push(Hazard::new(x, y, HazardKind::Hellfire, damage, ERUPTION_WARNING_TURNS, ERUPTION_BURN_TURNS));
+                    }
+                }
+                game.messages.add(format!("The {} gestures, and the floor starts to glow with heat!", boss_name), LIGHT_RED);
+            }
+            usable
+        }
+    };
+    if used {
+        game.add_event(GameEvent::from_type_with_data(
+            EventType::MonsterCastSpell,
+            HashMap::from([
+                ("caster_name".to_string(), EventData::String(boss_name)),
+                ("spell".to_string(), EventData::String(ability.to_string())),
+            ])
+        ));
+    }
+    used
+}
+
+fn flee(monster_id: usize, target_id: usize, game: &mut GameEngine) {
+    let (target_x, target_y) = game.entities[target_id].pos();
     let map: &Map = &game.map;
-    let entities = game.entities.borrow_mut();
-    move_towards(monster_id, x, y, map, entities);
-    if num_turns == 0 {
-        messages.add(format!("The {} is no longer confused", game.entities[monster_id].name), RED);
-        *previous_ai
+    if move_away_from(monster_id, target_x, target_y, map, &mut game.entities) {
+        game.add_event(GameEvent::from_type(EventType::MonsterMove));
     } else {
-        Ai::Confused{ previous_ai: previous_ai, num_turns: num_turns - 1}
+        // cornered, so it may as well fight
+        chase_and_attack(monster_id, target_id, game);
+    }
+}
+
+fn wander(monster_id: usize, game: &mut GameEngine) {
+    if rand::thread_rng().gen_range(0, 100) >= WANDER_MOVE_CHANCE {
+        return;
+    }
+    let mut awareness = match game.entities[monster_id].awareness {
+        Some(awareness) => awareness,
+        None => return,
+    };
+    let pos = game.entities[monster_id].pos();
+    let target = match awareness.wander_target {
+        Some(target) if target != pos => target,
+        _ => random_floor_tile(&game.map),
+    };
+    let map: &Map = &game.map;
+    move_towards(monster_id, target.0, target.1, map, &mut game.entities);
+    // a monster that walked into a wall gives up on that spot and picks somewhere else next time
+    awareness.wander_target = if game.entities[monster_id].pos() == pos { None } else { Some(target) };
+    game.entities[monster_id].awareness = Some(awareness);
+    game.add_event(GameEvent::from_type(EventType::MonsterMove));
+}
+
+fn random_floor_tile(map: &Map) -> (i32, i32) {
+    loop {
+        let x = rand::thread_rng().gen_range(0, MAP_WIDTH);
+        let y = rand::thread_rng().gen_range(0, MAP_HEIGHT);
+        if !map[x as usize][y as usize].blocked {
+            return (x, y);
+        }
     }
-}
\ No newline at end of file
+}
+
+fn ai_confused(monster_id:usize, game: &mut GameEngine) {
+    // stumble in a random direction, lashing out at whatever is in the way
+    let (dx, dy) = random_direction();
+    let map: &Map = &game.map;
+    let entities: &mut Vec<Entity> = game.entities.borrow_mut();
+    let event_bus = game.event_bus.borrow_mut();
+    let (x, y) = entities[monster_id].pos();
+    let target_id = entities.iter().position(|e| e.fighter.is_some() && e.pos() == (x + dx, y + dy));
+    match target_id {
+        Some(target_id) if target_id != monster_id => {
+            let (monster, target) = mut_two(monster_id, target_id, entities);
+            monster.attack(target, event_bus);
+            event_bus.add_event(GameEvent::from_type(EventType::MonsterAttack));
+        }
+        _ => move_by(monster_id, dx, dy, map, entities)
+    }
+}
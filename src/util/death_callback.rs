@@ -2,6 +2,9 @@ use std::collections::HashMap;
 use tcod::colors::DARK_RED;
 use crate::{Entity, EventBus, EventData, EventType, GameEvent};
 use serde::{Deserialize, Serialize};
+use crate::entities::entity_actions::noisy_event;
+use crate::items::corpse::corpse_of;
+use crate::map::noise::NOISE_DEATH;
 
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
@@ -36,10 +39,12 @@ fn monster_death(monster: &mut Entity, event_bus: &mut EventBus) {
     monster.char = '%';
     monster.color = DARK_RED;
     monster.blocks = false;
+    monster.item = Some(corpse_of(monster));
     monster.fighter = None;
     monster.ai = None;
+    monster.companion = None;
     monster.name = format!("remains of {}", monster.name);
-    event_bus.add_event(GameEvent::from_type(EventType::MonsterDie));
+    event_bus.add_event(noisy_event(EventType::MonsterDie, monster.pos(), NOISE_DEATH));
 }
 
 fn boss_death(monster: &mut Entity, event_bus: &mut EventBus) {
@@ -47,11 +52,15 @@ fn boss_death(monster: &mut Entity, event_bus: &mut EventBus) {
     monster.char = '%';
     monster.color = DARK_RED;
     monster.blocks = false;
+    monster.item = Some(corpse_of(monster));
     monster.fighter = None;
     monster.ai = None;
     monster.name = format!("remains of {}", monster.name);
     event_bus.add_event(GameEvent::from_type_with_data(
         EventType::BossDie,
-        HashMap::from([("position".to_string(), EventData::TupleI32I32(monster.pos()))])
+        HashMap::from([
+            ("position".to_string(), EventData::TupleI32I32(monster.pos())),
+            ("noise".to_string(), EventData::I32(NOISE_DEATH)),
+        ])
     ));
 }
\ No newline at end of file
@@ -1,4 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
 pub struct Transition {
     pub level: u32,
     pub value: u32,
-}
\ No newline at end of file
+}